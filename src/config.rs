@@ -0,0 +1,226 @@
+pub mod file {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use serde::Deserialize;
+
+    use crate::stats::profile::Profile;
+    use crate::ui::theme::{rgb, Rgb};
+
+    /// Overrides where the config file is read from.
+    pub const ENV: &str = "WORDLE_RS_CONFIG";
+
+    /**
+     * Defaults for the command line options, e.g.
+     *
+     * ```toml
+     * ascii = true
+     * word-len = 6
+     * hard = true
+     * ```
+     *
+     * Anything left out keeps its built-in default, and flags on the command line win.
+     */
+    #[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+    #[serde(deny_unknown_fields, rename_all = "kebab-case")]
+    pub struct Config {
+        pub word_len: Option<usize>,
+        pub inline: Option<bool>,
+        pub ascii: Option<bool>,
+        pub unicode: Option<bool>,
+        pub guesses: Option<usize>,
+        pub hard: Option<bool>,
+        /// In seconds.
+        pub time_limit: Option<u64>,
+        pub dict: Option<String>,
+        pub share: Option<bool>,
+        pub copy: Option<bool>,
+        pub no_emoji: Option<bool>,
+        pub no_animation: Option<bool>,
+        pub keyboard: Option<String>,
+        pub leaderboard: Option<PathBuf>,
+        pub theme: Option<String>,
+        /// For `theme = "custom"`.
+        pub colors: Option<Colors>,
+    }
+
+    /// `#rrggbb` colors for each tile state, under `[colors]`.
+    #[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+    #[serde(deny_unknown_fields)]
+    pub struct Colors {
+        pub miss: String,
+        pub close: String,
+        #[serde(rename = "match")]
+        pub matched: String,
+    }
+
+    impl Colors {
+        pub fn rgb(&self) -> Result<[Rgb; 3], String> {
+            Ok([rgb(&self.miss)?, rgb(&self.close)?, rgb(&self.matched)?])
+        }
+    }
+
+    /**
+     * `$WORDLE_RS_CONFIG`, else the profile's own `config.toml` if it has one, else `config.toml`
+     * under the XDG config directory.
+     */
+    pub fn path(profile: Option<&Profile>) -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(ENV) {
+            return Some(PathBuf::from(path));
+        }
+        match profile.map(Profile::config_path) {
+            Some(path) if path.exists() => Some(path),
+            _ => dirs::config_dir().map(|d| d.join("wordle-rs").join("config.toml")),
+        }
+    }
+
+    /// Reads the config at `path`. A missing file is the same as an empty one.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join(format!("wordle-rs-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+
+        assert_eq!(load(&path), Ok(Config::default()));
+
+        fs::write(&path, "ascii = true\nword-len = 6\ndict = \"loose\"\n").unwrap();
+        let config = load(&path).unwrap();
+        assert_eq!(config.ascii, Some(true));
+        assert_eq!(config.word_len, Some(6));
+        assert_eq!(config.dict.as_deref(), Some("loose"));
+        assert_eq!(config.hard, None);
+
+        fs::write(
+            &path,
+            "theme = \"custom\"\n[colors]\nmiss = \"#000000\"\nclose = \"#ff8000\"\nmatch = \"#0000ff\"\n",
+        )
+        .unwrap();
+        let colors = load(&path).unwrap().colors.unwrap();
+        assert_eq!(colors.rgb(), Ok([(0, 0, 0), (0xff, 0x80, 0), (0, 0, 0xff)]));
+
+        fs::write(&path, "wordlen = 6\n").unwrap();
+        assert!(load(&path).unwrap_err().contains("unknown field"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+pub mod effective {
+    use std::fmt::{self, Display, Write};
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum Source {
+        Default,
+        File,
+        Env(&'static str),
+        Cli,
+    }
+
+    impl Display for Source {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Source::Default => write!(f, "default"),
+                Source::File => write!(f, "config file"),
+                Source::Env(var) => write!(f, "${}", var),
+                Source::Cli => write!(f, "command line"),
+            }
+        }
+    }
+
+    /// Each setting's final value and where it came from, for `config show`.
+    #[derive(Debug, Default, Clone)]
+    pub struct Settings {
+        entries: Vec<(&'static str, String, Source)>,
+    }
+
+    impl Settings {
+        /// The command line's value if it was `given`, else the config file's, else `cli`'s
+        /// (which is then clap's default).
+        pub fn pick<T: Display>(
+            &mut self,
+            key: &'static str,
+            given: bool,
+            cli: T,
+            file: Option<T>,
+        ) -> T {
+            let (value, source) = match file {
+                Some(v) if !given => (v, Source::File),
+                _ if given => (cli, Source::Cli),
+                _ => (cli, Source::Default),
+            };
+            self.record(key, value.to_string(), source);
+            value
+        }
+
+        /// Like [Settings::pick], for options that can be left unset.
+        pub fn pick_option<T: Display>(
+            &mut self,
+            key: &'static str,
+            given: bool,
+            cli: Option<T>,
+            file: Option<T>,
+        ) -> Option<T> {
+            let (value, source) = match file {
+                Some(v) if !given => (Some(v), Source::File),
+                _ if given => (cli, Source::Cli),
+                _ => (cli, Source::Default),
+            };
+            let shown = value.as_ref().map_or("none".to_string(), |v| v.to_string());
+            self.record(key, shown, source);
+            value
+        }
+
+        pub fn record(&mut self, key: &'static str, value: String, source: Source) {
+            self.entries.push((key, value, source));
+        }
+
+        pub fn get(&self, key: &str) -> Option<(&str, Source)> {
+            self.entries
+                .iter()
+                .find(|(k, _, _)| *k == key)
+                .map(|(_, v, s)| (v.as_str(), *s))
+        }
+
+        pub fn render(&self) -> String {
+            let key_width = self.entries.iter().map(|e| e.0.len()).max().unwrap_or(0);
+            let value_width = self.entries.iter().map(|e| e.1.len()).max().unwrap_or(0);
+            let mut out = String::new();
+            for (key, value, source) in &self.entries {
+                writeln!(
+                    out,
+                    "{:kw$}  {:vw$}  ({})",
+                    key,
+                    value,
+                    source,
+                    kw = key_width,
+                    vw = value_width
+                )
+                .unwrap();
+            }
+            out
+        }
+    }
+
+    #[test]
+    fn test_pick() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.pick("word-len", true, 7, Some(6)), 7);
+        assert_eq!(settings.pick("guesses", false, 6, Some(8)), 8);
+        assert!(!settings.pick("hard", false, false, None));
+        assert_eq!(settings.get("word-len"), Some(("7", Source::Cli)));
+        assert_eq!(settings.get("guesses"), Some(("8", Source::File)));
+        assert_eq!(settings.get("hard"), Some(("false", Source::Default)));
+        assert_eq!(
+            settings.render().lines().nth(1),
+            Some("guesses   8      (config file)")
+        );
+    }
+}
@@ -0,0 +1,473 @@
+pub mod protocol {
+    /*!
+     * A line-based protocol for bots, in the spirit of chess engines' UCI. Tiles are sent in
+     * their ASCII form (see [crate::wordl::ASCII_MAP]). A session looks like:
+     *
+     * ```text
+     * host: wordle-rs 2                        protocol version
+     * bot:  ready [name]
+     * host: new <len> <guesses> <normal|hard> <seconds|->
+     *                                          the time limit, or - for none
+     * host: go                                 the bot's turn to guess
+     * bot:  guess <word>
+     * host: result <tiles>                     e.g. result x~@xx
+     * host: error <kind> <message>             kind is length, unknown, hard, time, over or
+     *                                          syntax
+     * host: over <won|lost> <guesses> <answer>
+     * host: quit
+     * ```
+     *
+     * Each `go` is answered with exactly one `guess`, which gets a `result` or an `error`.
+     * `new` through `over` repeats for every game in the session.
+     */
+
+    use std::fmt;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use crate::wordl::{join, split, Rules};
+
+    pub const VERSION: u32 = 2;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum HostMsg {
+        Hello(u32),
+        New {
+            len: usize,
+            rules: Rules,
+        },
+        Go,
+        Result(Vec<char>),
+        Error {
+            kind: String,
+            message: String,
+        },
+        Over {
+            won: bool,
+            guesses: usize,
+            answer: String,
+        },
+        Quit,
+    }
+
+    impl fmt::Display for HostMsg {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                HostMsg::Hello(version) => write!(f, "wordle-rs {}", version),
+                HostMsg::New { len, rules } => write!(
+                    f,
+                    "new {} {} {} {}",
+                    len,
+                    rules.guesses,
+                    if rules.hard { "hard" } else { "normal" },
+                    rules
+                        .time_limit
+                        .map_or("-".to_string(), |limit| limit.as_secs().to_string())
+                ),
+                HostMsg::Go => write!(f, "go"),
+                HostMsg::Result(cmp) => write!(f, "result {}", join(cmp, true)),
+                HostMsg::Error { kind, message } => write!(f, "error {} {}", kind, message),
+                HostMsg::Over {
+                    won,
+                    guesses,
+                    answer,
+                } => write!(
+                    f,
+                    "over {} {} {}",
+                    if *won { "won" } else { "lost" },
+                    guesses,
+                    answer
+                ),
+                HostMsg::Quit => write!(f, "quit"),
+            }
+        }
+    }
+
+    impl FromStr for HostMsg {
+        type Err = String;
+
+        fn from_str(line: &str) -> Result<Self, Self::Err> {
+            let bad = || format!("Unrecognized host message {:?}", line);
+            let mut words = line.split_whitespace();
+
+            match words.next() {
+                Some("wordle-rs") => Ok(HostMsg::Hello(num(words.next()).ok_or_else(bad)?)),
+                Some("new") => {
+                    let len = num(words.next()).ok_or_else(bad)?;
+                    let guesses = num(words.next()).ok_or_else(bad)?;
+                    let hard = match words.next() {
+                        Some("normal") => false,
+                        Some("hard") => true,
+                        _ => return Err(bad()),
+                    };
+                    let time_limit = match words.next() {
+                        Some("-") => None,
+                        secs => Some(Duration::from_secs(num(secs).ok_or_else(bad)?)),
+                    };
+                    Ok(HostMsg::New {
+                        len,
+                        rules: Rules {
+                            guesses,
+                            hard,
+                            time_limit,
+                        },
+                    })
+                }
+                Some("go") => Ok(HostMsg::Go),
+                Some("result") => words
+                    .next()
+                    .and_then(split)
+                    .map(HostMsg::Result)
+                    .ok_or_else(bad),
+                Some("error") => Ok(HostMsg::Error {
+                    kind: words.next().ok_or_else(bad)?.to_string(),
+                    message: words.collect::<Vec<_>>().join(" "),
+                }),
+                Some("over") => {
+                    let won = match words.next() {
+                        Some("won") => true,
+                        Some("lost") => false,
+                        _ => return Err(bad()),
+                    };
+                    Ok(HostMsg::Over {
+                        won,
+                        guesses: num(words.next()).ok_or_else(bad)?,
+                        answer: words.next().ok_or_else(bad)?.to_string(),
+                    })
+                }
+                Some("quit") => Ok(HostMsg::Quit),
+                _ => Err(bad()),
+            }
+        }
+    }
+
+    fn num<T: FromStr>(word: Option<&str>) -> Option<T> {
+        word.and_then(|w| w.parse().ok())
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum BotMsg {
+        Ready(Option<String>),
+        Guess(String),
+    }
+
+    impl fmt::Display for BotMsg {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                BotMsg::Ready(None) => write!(f, "ready"),
+                BotMsg::Ready(Some(name)) => write!(f, "ready {}", name),
+                BotMsg::Guess(word) => write!(f, "guess {}", word),
+            }
+        }
+    }
+
+    impl FromStr for BotMsg {
+        type Err = String;
+
+        fn from_str(line: &str) -> Result<Self, Self::Err> {
+            match line.trim().split_once(' ') {
+                None if line.trim() == "ready" => Ok(BotMsg::Ready(None)),
+                Some(("ready", name)) => Ok(BotMsg::Ready(Some(name.trim().to_string()))),
+                Some(("guess", word)) if !word.trim().contains(' ') => {
+                    Ok(BotMsg::Guess(word.trim().to_string()))
+                }
+                _ => Err(format!("Expected \"guess <word>\", got {:?}", line.trim())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_protocol() {
+        use crate::wordl::{CLOSE, MATCH, MISS};
+
+        let msgs = vec![
+            HostMsg::Hello(VERSION),
+            HostMsg::New {
+                len: 5,
+                rules: Rules {
+                    guesses: 6,
+                    hard: true,
+                    ..Rules::default()
+                },
+            },
+            HostMsg::New {
+                len: 6,
+                rules: Rules {
+                    time_limit: Some(Duration::from_secs(30)),
+                    ..Rules::default()
+                },
+            },
+            HostMsg::Go,
+            HostMsg::Result(vec![MISS, CLOSE, MATCH]),
+            HostMsg::Error {
+                kind: "unknown".to_string(),
+                message: "Guess \"ABCDE\" not in word list!".to_string(),
+            },
+            HostMsg::Over {
+                won: false,
+                guesses: 6,
+                answer: "SLUMP".to_string(),
+            },
+            HostMsg::Quit,
+        ];
+        for msg in msgs {
+            assert_eq!(msg.to_string().parse::<HostMsg>(), Ok(msg));
+        }
+        assert!("new 5 6 hard".parse::<HostMsg>().is_err());
+
+        assert_eq!("ready".parse(), Ok(BotMsg::Ready(None)));
+        assert_eq!(
+            "ready rando bot".parse(),
+            Ok(BotMsg::Ready(Some("rando bot".to_string())))
+        );
+        assert_eq!(
+            "guess slump\n".parse(),
+            Ok(BotMsg::Guess("slump".to_string()))
+        );
+        assert!("guess two words".parse::<BotMsg>().is_err());
+        assert!("slump".parse::<BotMsg>().is_err());
+    }
+}
+
+pub mod host {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::{Command, Stdio};
+
+    use crate::dicts::DICT;
+    use crate::engine::protocol::{BotMsg, HostMsg, VERSION};
+    use crate::wordl::{Game, Rules};
+
+    /// Bad messages a bot may send in one game before it forfeits.
+    pub const MAX_ERRORS: usize = 10;
+
+    #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    pub struct Score {
+        pub played: usize,
+        pub won: usize,
+        /// Guesses taken, summed over the games that were won.
+        pub guesses: usize,
+    }
+
+    impl Score {
+        pub fn average(&self) -> f64 {
+            if self.won == 0 {
+                0.0
+            } else {
+                self.guesses as f64 / self.won as f64
+            }
+        }
+    }
+
+    fn send(to_bot: &mut dyn Write, msg: HostMsg) -> Result<(), String> {
+        writeln!(to_bot, "{}", msg)
+            .and_then(|_| to_bot.flush())
+            .map_err(|e| format!("Bot stopped listening: {}", e))
+    }
+
+    fn receive(from_bot: &mut dyn BufRead) -> Result<String, String> {
+        let mut line = String::new();
+        match from_bot.read_line(&mut line) {
+            Ok(0) => Err("Bot exited.".to_string()),
+            Ok(_) => Ok(line),
+            Err(e) => Err(format!("Bot stopped talking: {}", e)),
+        }
+    }
+
+    /**
+     * Plays one game against the bot for every answer, logging a line per game. Fails only if
+     * the bot exits or breaks the handshake; bad guesses are answered with an `error`.
+     */
+    pub fn host(
+        from_bot: &mut dyn BufRead,
+        to_bot: &mut dyn Write,
+        answers: &[String],
+        rules: Rules,
+        log: &mut dyn Write,
+    ) -> Result<Score, String> {
+        send(to_bot, HostMsg::Hello(VERSION))?;
+        let name = match receive(from_bot)?.parse() {
+            Ok(BotMsg::Ready(name)) => name.unwrap_or_else(|| "Bot".to_string()),
+            _ => return Err("Bot did not say it was ready.".to_string()),
+        };
+
+        let mut score = Score::default();
+        for answer in answers {
+            let mut game = Game::with_rules(answer, rules);
+            send(
+                to_bot,
+                HostMsg::New {
+                    len: game.len(),
+                    rules,
+                },
+            )?;
+
+            let mut errors = 0;
+            while 0 < game.guesses_remaining() && errors < MAX_ERRORS {
+                send(to_bot, HostMsg::Go)?;
+                let reply = match receive(from_bot)?.parse() {
+                    Ok(BotMsg::Guess(word)) => match game.guess(&word) {
+                        Ok(cmp) => HostMsg::Result(cmp),
+                        Err(e) => HostMsg::Error {
+                            kind: e.kind().to_string(),
+                            message: e.to_string(),
+                        },
+                    },
+                    Ok(BotMsg::Ready(_)) => HostMsg::Error {
+                        kind: "syntax".to_string(),
+                        message: "Already ready, expected a guess.".to_string(),
+                    },
+                    Err(message) => HostMsg::Error {
+                        kind: "syntax".to_string(),
+                        message,
+                    },
+                };
+                if let HostMsg::Error { .. } = reply {
+                    errors += 1;
+                }
+                send(to_bot, reply)?;
+            }
+
+            score.played += 1;
+            if game.is_won() {
+                score.won += 1;
+                score.guesses += game.guesses_made();
+            }
+            writeln!(
+                log,
+                "Game {}: {} {} {} in {}{}",
+                score.played,
+                name,
+                if game.is_won() { "solved" } else { "missed" },
+                game.actual(),
+                game.guesses_made(),
+                if MAX_ERRORS <= errors {
+                    " (forfeit)"
+                } else {
+                    ""
+                },
+            )
+            .unwrap();
+            send(
+                to_bot,
+                HostMsg::Over {
+                    won: game.is_won(),
+                    guesses: game.guesses_made(),
+                    answer: game.actual().to_string(),
+                },
+            )?;
+        }
+        send(to_bot, HostMsg::Quit)?;
+
+        Ok(score)
+    }
+
+    /// Spawns `command` and hosts `games` random games of length `word_len`, or of `word`.
+    pub fn host_bot(
+        command: &[String],
+        games: usize,
+        word_len: usize,
+        word: Option<String>,
+        rules: Rules,
+    ) -> Result<(), String> {
+        let (program, args) = command.split_first().ok_or("No bot command given.")?;
+        let answers: Vec<String> = match word {
+            Some(w) => vec![w; games],
+            None => (0..games)
+                .map(|_| DICT.rand_of_len(word_len))
+                .collect::<Result<_, _>>()?,
+        };
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Could not start {:?}: {}", program, e))?;
+
+        let mut to_bot = child.stdin.take().unwrap();
+        let mut from_bot = BufReader::new(child.stdout.take().unwrap());
+        let result = host(
+            &mut from_bot,
+            &mut to_bot,
+            &answers,
+            rules,
+            &mut std::io::stdout(),
+        );
+
+        drop(to_bot);
+        let _ = child.wait();
+
+        let score = result?;
+        println!(
+            "Won {} of {} ({:.0}%), averaging {:.2} guesses.",
+            score.won,
+            score.played,
+            100.0 * score.won as f64 / score.played.max(1) as f64,
+            score.average(),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_host() {
+        use std::io::Cursor;
+
+        let mut from_bot = Cursor::new(
+            b"ready tester\nguess tight\nguess slumpy\nguess\nguess slump\nready\nguess slump\n"
+                .to_vec(),
+        );
+        let mut to_bot: Vec<u8> = Vec::new();
+        let mut log: Vec<u8> = Vec::new();
+
+        let score = host(
+            &mut from_bot,
+            &mut to_bot,
+            &["SLUMP".to_string(), "SLUMP".to_string()],
+            Rules::default(),
+            &mut log,
+        )
+        .unwrap();
+
+        assert_eq!(
+            score,
+            Score {
+                played: 2,
+                won: 2,
+                guesses: 3,
+            }
+        );
+
+        let sent = String::from_utf8(to_bot).unwrap();
+        let kinds: Vec<&str> = sent.lines().map(|l| l.split(' ').next().unwrap()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "wordle-rs",
+                "new",
+                "go",
+                "result",
+                "go",
+                "error",
+                "go",
+                "error",
+                "go",
+                "result",
+                "over",
+                "new",
+                "go",
+                "error",
+                "go",
+                "result",
+                "over",
+                "quit"
+            ]
+        );
+        assert!(sent.contains("error length"));
+        assert!(sent.contains("error syntax"));
+        assert!(sent.contains("over won 2 SLUMP"));
+        assert!(String::from_utf8(log)
+            .unwrap()
+            .starts_with("Game 1: tester solved SLUMP in 2"));
+    }
+}
@@ -1,11 +1,27 @@
+pub mod config;
+pub mod engine;
+pub mod solve;
+pub mod stats;
 pub mod ui;
 
 pub mod wordl {
     #![allow(clippy::len_without_is_empty)]
 
+    use std::fmt;
     use std::io::{BufRead, BufReader, Read, Write};
+    use std::time::{Duration, Instant};
 
     use crate::dicts::DICT;
+    use crate::solve::pattern::{self, Pattern};
+    use crate::stats::breakdown::Detail;
+    use crate::stats::leaderboard::Board;
+    use crate::stats::store::{Record, Store};
+    use crate::stats::summary::{Filter, Mode, Summary};
+    use crate::ui::backend::{Backend, Crossterm};
+    use crate::ui::position::Position;
+    use crate::ui::screen::Screen;
+    use crate::ui::term::{Res, Targets};
+    use crate::ui::theme::{self, Theme};
 
     pub const MISS: char = '🟥';
     pub const CLOSE: char = '🟨';
@@ -72,63 +88,248 @@ pub mod wordl {
 
     pub const MAX_GUESSES: usize = 6;
 
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct Rules {
+        pub guesses: usize,
+        /// Every revealed hint must be used in subsequent guesses.
+        pub hard: bool,
+        /// The game is lost once this much time has passed.
+        pub time_limit: Option<Duration>,
+    }
+
+    impl Default for Rules {
+        fn default() -> Self {
+            Self {
+                guesses: MAX_GUESSES,
+                hard: false,
+                time_limit: None,
+            }
+        }
+    }
+
+    /// A duration as `m:ss.t`.
+    pub fn clock(d: Duration) -> String {
+        let secs = d.as_secs();
+        format!("{}:{:02}.{}", secs / 60, secs % 60, d.subsec_millis() / 100)
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum GuessError {
+        Over { won: bool },
+        Length { guess: String, len: usize },
+        NotInList(String),
+        Hard(String),
+        OutOfTime,
+    }
+
+    impl GuessError {
+        /// A stable, single-word name for the error, for machine consumers.
+        pub fn kind(&self) -> &'static str {
+            match self {
+                GuessError::Over { .. } => "over",
+                GuessError::Length { .. } => "length",
+                GuessError::NotInList(_) => "unknown",
+                GuessError::Hard(_) => "hard",
+                GuessError::OutOfTime => "time",
+            }
+        }
+    }
+
+    impl fmt::Display for GuessError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                GuessError::Over { won } => write!(
+                    f,
+                    "No guesses remaining! {}",
+                    if *won { "You won!" } else { "You lost!" }
+                ),
+                GuessError::Length { guess, len } => {
+                    write!(f, "Guess {:?} not of length {}!", guess, len)
+                }
+                GuessError::NotInList(guess) => write!(f, "Guess {:?} not in word list!", guess),
+                GuessError::Hard(msg) => write!(f, "{}", msg),
+                GuessError::OutOfTime => write!(f, "Out of time!"),
+            }
+        }
+    }
+
+    impl From<GuessError> for String {
+        fn from(e: GuessError) -> Self {
+            e.to_string()
+        }
+    }
+
+    /// Each hint taken adds this many guesses to the game's [Game::score].
+    pub const HINT_PENALTY: usize = 1;
+
+    /// Hints escalate in tiers: first a letter somewhere in the word, then a letter at its
+    /// position, then how many words are still possible.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum Hint {
+        Letter(char),
+        Position(usize, char),
+        Candidates(usize),
+    }
+
+    impl fmt::Display for Hint {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Hint::Letter(ch) => write!(f, "The word contains {}.", ch),
+                Hint::Position(i, ch) => write!(f, "Letter {} is {}.", i + 1, ch),
+                Hint::Candidates(1) => write!(f, "Only 1 word fits."),
+                Hint::Candidates(n) => write!(f, "{} words still fit.", n),
+            }
+        }
+    }
+
+    /// The first daily puzzle, number 0.
+    pub const DAILY_EPOCH: (i32, u32, u32) = (2022, 1, 1);
+
+    /// The daily puzzle number for a local date.
+    pub fn puzzle_number(date: chrono::NaiveDate) -> u32 {
+        let (y, m, d) = DAILY_EPOCH;
+        let epoch = chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        (date - epoch).num_days().max(0) as u32
+    }
+
+    pub fn todays_puzzle() -> u32 {
+        puzzle_number(chrono::Local::now().date_naive())
+    }
+
     pub struct Game {
         actual: String,
+        rules: Rules,
         guesses: Vec<(String, Vec<char>)>,
+        hints: Vec<Hint>,
+        puzzle: Option<u32>,
+        started: Instant,
+        /// Time on the clock at each accepted guess.
+        times: Vec<Duration>,
     }
 
     impl Game {
         pub fn new(actual: &str) -> Self {
+            Self::with_rules(actual, Rules::default())
+        }
+
+        pub fn with_rules(actual: &str, rules: Rules) -> Self {
             Self {
                 actual: normalize(actual),
-                guesses: Vec::with_capacity(6),
+                rules,
+                guesses: Vec::with_capacity(rules.guesses),
+                hints: Vec::new(),
+                puzzle: None,
+                started: Instant::now(),
+                times: Vec::with_capacity(rules.guesses),
             }
         }
 
-        pub fn with_len(len: usize) -> Self {
-            Self::new(&crate::dicts::DICT.rand_of_len(len))
+        pub fn with_len(len: usize) -> Result<Self, String> {
+            Ok(Self::new(&crate::dicts::DICT.rand_of_len(len)?))
         }
 
-        pub fn guess(&mut self, guess_raw: &str) -> Result<Vec<char>, String> {
+        /**
+         * Daily puzzle `number`, which is the same word for everyone playing that length with
+         * the same dictionary.
+         */
+        pub fn daily(len: usize, number: u32, rules: Rules) -> Result<Self, String> {
+            let mut game = Self::with_rules(&DICT.daily(len, number)?, rules);
+            game.puzzle = Some(number);
+            Ok(game)
+        }
+
+        pub fn puzzle(&self) -> Option<u32> {
+            self.puzzle
+        }
+
+        pub fn guess(&mut self, guess_raw: &str) -> Result<Vec<char>, GuessError> {
             let guess = normalize(guess_raw);
 
+            if self.is_out_of_time() {
+                return Err(GuessError::OutOfTime);
+            }
             if self.guesses_remaining() < 1 {
-                return Err(format!(
-                    "No guesses remaining! {}",
-                    if self.is_won() {
-                        "You won!"
-                    } else {
-                        "You lost!"
-                    },
-                ));
+                return Err(GuessError::Over { won: self.is_won() });
             }
             if guess.len() != self.len() {
-                return Err(format!("Guess {:?} not of length {}!", guess, self.len()));
+                return Err(GuessError::Length {
+                    guess,
+                    len: self.len(),
+                });
             }
             if !crate::dicts::DICT.has(&guess) && guess != self.actual {
-                return Err(format!("Guess {:?} not in word list!", guess));
+                return Err(GuessError::NotInList(guess));
+            }
+            if self.rules.hard {
+                self.check_hard(&guess)?;
             }
 
             let cmp = compare(&self.actual, &guess);
             self.guesses.push((guess, cmp.clone()));
+            self.times.push(self.started.elapsed());
 
             Ok(cmp)
         }
 
+        fn check_hard(&self, guess: &str) -> Result<(), GuessError> {
+            let chars: Vec<char> = guess.chars().collect();
+
+            for (prev, cmp) in &self.guesses {
+                for (i, (pc, &c)) in prev.chars().zip(cmp.iter()).enumerate() {
+                    if c == MATCH && chars[i] != pc {
+                        return Err(GuessError::Hard(format!(
+                            "Letter {} must be {}!",
+                            i + 1,
+                            pc
+                        )));
+                    }
+                }
+
+                for (pc, &c) in prev.chars().zip(cmp.iter()) {
+                    if c == MISS {
+                        continue;
+                    }
+                    let revealed = prev
+                        .chars()
+                        .zip(cmp.iter())
+                        .filter(|&(ch, &c)| ch == pc && c != MISS)
+                        .count();
+                    if chars.iter().filter(|&&ch| ch == pc).count() < revealed {
+                        return Err(GuessError::Hard(format!("Guess must contain {}!", pc)));
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
         pub fn len(&self) -> usize {
             self.actual.len()
         }
 
+        pub fn actual(&self) -> &str {
+            &self.actual
+        }
+
+        pub fn rules(&self) -> Rules {
+            self.rules
+        }
+
+        /// Every accepted guess with its comparison, in order.
+        pub fn history(&self) -> &[(String, Vec<char>)] {
+            &self.guesses
+        }
+
         pub fn guesses_made(&self) -> usize {
             self.guesses.len()
         }
 
         pub fn guesses_remaining(&self) -> usize {
-            if self.is_won() {
+            if self.is_won() || self.is_out_of_time() {
                 return 0;
             }
 
-            MAX_GUESSES - self.guesses.len()
+            self.rules.guesses - self.guesses.len()
         }
 
         pub fn is_won(&self) -> bool {
@@ -140,6 +341,217 @@ pub mod wordl {
 
             false
         }
+
+        /// Starts the clock over, e.g. once the board is actually on screen.
+        pub fn start_clock(&mut self) {
+            self.started = Instant::now();
+        }
+
+        /// Time on the clock: stopped at the last guess once the game is over, and never past
+        /// the time limit.
+        pub fn elapsed(&self) -> Duration {
+            if self.is_won() || self.guesses.len() == self.rules.guesses {
+                return self.times.last().copied().unwrap_or_default();
+            }
+            let now = self.started.elapsed();
+            self.rules.time_limit.map_or(now, |limit| now.min(limit))
+        }
+
+        /// Time on the clock at each guess.
+        pub fn times(&self) -> &[Duration] {
+            &self.times
+        }
+
+        pub fn time_left(&self) -> Option<Duration> {
+            self.rules
+                .time_limit
+                .map(|limit| limit.saturating_sub(self.elapsed()))
+        }
+
+        /// Whether the time limit ran out before the game was otherwise over.
+        pub fn is_out_of_time(&self) -> bool {
+            self.time_left() == Some(Duration::ZERO)
+                && !self.is_won()
+                && self.guesses.len() < self.rules.guesses
+        }
+
+        /**
+         * Reveals the next hint tier. A tier with nothing left to reveal (e.g. every letter
+         * is already known) is skipped in favor of the next one.
+         */
+        pub fn hint(&mut self) -> Result<Hint, String> {
+            if self.guesses_remaining() < 1 {
+                return Err("No hints once the game is over!".to_string());
+            }
+
+            let hint = match self.hints.len() {
+                0 => self.letter_hint().or_else(|| self.position_hint()),
+                1 => self.position_hint(),
+                _ => None,
+            }
+            .unwrap_or_else(|| Hint::Candidates(self.candidates().len()));
+            self.hints.push(hint);
+
+            Ok(hint)
+        }
+
+        pub fn hints_used(&self) -> usize {
+            self.hints.len()
+        }
+
+        /// Guesses made plus the [HINT_PENALTY] for every hint taken.
+        pub fn score(&self) -> usize {
+            self.guesses_made() + self.hints_used() * HINT_PENALTY
+        }
+
+        /// Words that would produce the same feedback as every guess made so far and agree with
+        /// every hint revealed.
+        pub fn candidates(&self) -> Vec<String> {
+            let mut words = DICT.word_lens(self.len());
+            if !DICT.has(&self.actual) {
+                words.push(self.actual.clone());
+            }
+
+            // Packed patterns save allocating two vectors per comparison, where they fit.
+            let packed = self.len() <= pattern::MAX_LEN
+                && self.guesses.iter().all(|(guess, _)| guess.is_ascii());
+            let targets: Vec<Pattern> = self
+                .guesses
+                .iter()
+                .map(|(_, cmp)| pattern::from_chars(cmp))
+                .collect();
+
+            words
+                .into_iter()
+                .filter(|w| {
+                    self.guesses
+                        .iter()
+                        .zip(&targets)
+                        .all(|((guess, cmp), &target)| {
+                            if packed && w.is_ascii() {
+                                pattern::of(w.as_bytes(), guess.as_bytes()) == target
+                            } else {
+                                &compare(w, guess) == cmp
+                            }
+                        })
+                })
+                .filter(|w| {
+                    self.hints.iter().all(|hint| match *hint {
+                        Hint::Letter(ch) => w.contains(ch),
+                        Hint::Position(i, ch) => w.chars().nth(i) == Some(ch),
+                        Hint::Candidates(_) => true,
+                    })
+                })
+                .collect()
+        }
+
+        /**
+         * The best thing known about a letter from the guesses so far: [MATCH] if it was ever
+         * matched, else [CLOSE], else [MISS]. [None] if it hasn't been guessed.
+         */
+        pub fn letter_state(&self, letter: char) -> Option<char> {
+            let rank = |c: char| ASCII_MAP.iter().position(|&(uni, _)| uni == c);
+            self.guesses
+                .iter()
+                .flat_map(|(guess, cmp)| guess.chars().zip(cmp.iter().copied()))
+                .filter(|&(gc, _)| gc == letter)
+                .map(|(_, c)| c)
+                .max_by_key(|&c| rank(c))
+        }
+
+        fn letter_hint(&self) -> Option<Hint> {
+            let mut known: Vec<char> = Vec::new();
+            for (guess, cmp) in &self.guesses {
+                for (gc, &c) in guess.chars().zip(cmp.iter()) {
+                    if c != MISS {
+                        known.push(gc);
+                    }
+                }
+            }
+            for hint in &self.hints {
+                match *hint {
+                    Hint::Letter(ch) | Hint::Position(_, ch) => known.push(ch),
+                    Hint::Candidates(_) => {}
+                }
+            }
+
+            self.actual
+                .chars()
+                .find(|ch| !known.contains(ch))
+                .map(Hint::Letter)
+        }
+
+        fn position_hint(&self) -> Option<Hint> {
+            self.actual
+                .chars()
+                .enumerate()
+                .find(|&(i, ch)| {
+                    let matched = self.guesses.iter().any(|(_, cmp)| cmp[i] == MATCH);
+                    let hinted = self.hints.contains(&Hint::Position(i, ch));
+                    !matched && !hinted
+                })
+                .map(|(i, ch)| Hint::Position(i, ch))
+        }
+
+        /// The line shown once the game is over.
+        pub fn summary(&self) -> String {
+            let hints = match self.hints_used() {
+                0 => String::new(),
+                1 => format!(" with 1 hint (score {})", self.score()),
+                n => format!(" with {} hints (score {})", n, self.score()),
+            };
+
+            if self.is_won() {
+                format!(
+                    "You got it in {} ({}){}!",
+                    self.guesses_made(),
+                    clock(self.elapsed()),
+                    hints
+                )
+            } else if self.is_out_of_time() {
+                format!("Out of time! The answer was {}.", self.actual)
+            } else {
+                format!("The answer was {}.", self.actual)
+            }
+        }
+
+        /**
+         * A spoiler-free block for sharing: a header like `wordle-rs #123 4/6* 1:23.4` (with `*`
+         * for hard mode, then the time taken) followed by the rows of tiles.
+         */
+        pub fn share(&self, ascii: bool) -> String {
+            let mut header = "wordle-rs".to_string();
+            if let Some(number) = self.puzzle {
+                header.push_str(&format!(" #{}", number));
+            }
+            if self.len() != 5 {
+                header.push_str(&format!(" ({} letters)", self.len()));
+            }
+            header.push_str(&format!(
+                " {}/{}{}",
+                if self.is_won() {
+                    self.guesses_made().to_string()
+                } else {
+                    "X".to_string()
+                },
+                self.rules.guesses,
+                if self.rules.hard { "*" } else { "" }
+            ));
+            match self.hints_used() {
+                0 => {}
+                1 => header.push_str(" +1 hint"),
+                n => header.push_str(&format!(" +{} hints", n)),
+            }
+            header.push_str(&format!(" {}", clock(self.elapsed())));
+
+            let mut block = header;
+            block.push('\n');
+            for (_, cmp) in &self.guesses {
+                block.push('\n');
+                block.push_str(&join(cmp, ascii));
+            }
+            block
+        }
     }
 
     #[test]
@@ -164,6 +576,123 @@ pub mod wordl {
         println!("Done");
     }
 
+    #[test]
+    fn test_hard() {
+        let rules = Rules {
+            hard: true,
+            ..Rules::default()
+        };
+        let mut game = Game::with_rules("slump", rules);
+        game.guess("maple").unwrap();
+
+        assert_eq!(game.guess("tight").unwrap_err().kind(), "hard");
+        assert_eq!(
+            game.guess("pluck").unwrap_err(),
+            GuessError::Hard("Guess must contain M!".to_string())
+        );
+        game.guess("plumb").unwrap();
+        assert_eq!(
+            game.guess("lumpy").unwrap_err(),
+            GuessError::Hard("Letter 2 must be L!".to_string())
+        );
+        game.guess("slump").unwrap();
+        assert!(game.is_won());
+    }
+
+    #[test]
+    fn test_share() {
+        assert!(Game::daily(30, 123, Rules::default()).is_err());
+        let mut game = Game::daily(5, 123, Rules::default()).unwrap();
+        game.actual = normalize("slump");
+        game.guess("plump").unwrap();
+        game.hint().unwrap();
+        game.guess("slump").unwrap();
+        game.times = vec![Duration::from_secs(20), Duration::from_millis(83_456)];
+        assert_eq!(
+            game.share(true),
+            "wordle-rs #123 2/6 +1 hint 1:23.4\n\nx@@@@\n@@@@@"
+        );
+
+        let mut game = Game::with_rules(
+            "slump",
+            Rules {
+                guesses: 1,
+                hard: true,
+                ..Rules::default()
+            },
+        );
+        game.guess("maple").unwrap();
+        game.times = vec![Duration::from_millis(4_321)];
+        assert_eq!(game.share(true), "wordle-rs X/1* 0:04.3\n\n~x~~x");
+
+        assert_eq!(
+            puzzle_number(chrono::NaiveDate::from_ymd_opt(2022, 5, 3).unwrap()),
+            122
+        );
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let limit = Duration::from_millis(50);
+        let mut game = Game::with_rules(
+            "slump",
+            Rules {
+                time_limit: Some(limit),
+                ..Rules::default()
+            },
+        );
+        game.guess("maple").unwrap();
+        assert_eq!(game.times().len(), 1);
+        assert!(game.time_left().unwrap() <= limit);
+
+        std::thread::sleep(limit);
+        assert!(game.is_out_of_time());
+        assert_eq!(game.guesses_remaining(), 0);
+        assert_eq!(game.elapsed(), limit);
+        assert_eq!(game.guess("slump"), Err(GuessError::OutOfTime));
+        assert_eq!(game.summary(), "Out of time! The answer was SLUMP.");
+
+        // A finished game stops the clock, limit or not.
+        let mut game = Game::new("slump");
+        game.guess("slump").unwrap();
+        let elapsed = game.elapsed();
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(game.elapsed(), elapsed);
+    }
+
+    #[test]
+    fn test_hint() {
+        let mut game = Game::new("slump");
+        game.guess("plump").unwrap();
+
+        assert_eq!(game.hint(), Ok(Hint::Letter('S')));
+        assert_eq!(game.hint(), Ok(Hint::Position(0, 'S')));
+        assert_eq!(game.hint(), Ok(Hint::Candidates(game.candidates().len())));
+        assert!(game.candidates().contains(&"SLUMP".to_string()));
+        assert_eq!(game.hints_used(), 3);
+
+        game.guess("slump").unwrap();
+        assert_eq!(game.score(), 2 + 3 * HINT_PENALTY);
+        assert!(game.hint().is_err());
+        game.times[1] = Duration::from_secs(5);
+        assert_eq!(
+            game.summary(),
+            "You got it in 2 (0:05.0) with 3 hints (score 5)!"
+        );
+    }
+
+    #[test]
+    fn test_letter_state() {
+        let mut game = Game::new("slump");
+        game.guess("sleep").unwrap();
+        game.guess("pulse").unwrap();
+        assert_eq!(game.letter_state('S'), Some(MATCH));
+        assert_eq!(game.letter_state('P'), Some(MATCH));
+        assert_eq!(game.letter_state('U'), Some(CLOSE));
+        assert_eq!(game.letter_state('E'), Some(MISS));
+        assert_eq!(game.letter_state('Z'), None);
+    }
+
     pub fn normalize(s: &str) -> String {
         s.to_uppercase()
     }
@@ -184,51 +713,201 @@ pub mod wordl {
             .collect::<String>()
     }
 
+    /**
+     * The inverse of [join]: reads a row of emoji (from any theme) or ASCII tiles back into a
+     * comparison, ignoring whitespace. Returns [None] if anything else is in the row.
+     */
+    pub fn split(row: &str) -> Option<Vec<char>> {
+        let cmp = row
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .map(|ch| {
+                ASCII_MAP
+                    .iter()
+                    .find(|&&(uni, asc)| ch == uni || ch == asc)
+                    .map(|&(uni, _)| uni)
+                    .or_else(|| theme::classic_emoji(ch))
+            })
+            .collect::<Option<Vec<char>>>()?;
+
+        if cmp.is_empty() {
+            None
+        } else {
+            Some(cmp)
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let cmp = vec![MISS, CLOSE, MATCH];
+        assert_eq!(split(&join(&cmp, false)), Some(cmp.clone()));
+        assert_eq!(split(&join(&cmp, true)), Some(cmp));
+        assert_eq!(split("wordle-rs 4/6"), None);
+        assert_eq!(split("  "), None);
+    }
+
     pub struct Opts {
         pub ascii: bool,
         pub word_len: usize,
         pub actual_raw: String,
+        pub rules: Rules,
+        /// Where finished games are recorded, if anywhere.
+        pub stats: Option<Store>,
+        /// Play this daily puzzle instead of `actual_raw`.
+        pub puzzle: Option<u32>,
+        /// Print a share block once the game is over.
+        pub share: bool,
+        /// Also copy the share block to the clipboard, through the terminal.
+        pub copy: bool,
+        /// Where daily results are posted for others to see, if anywhere.
+        pub leaderboard: Option<Board>,
+        /// Show the emoji (or ASCII) column next to each guess in [ui], as well as colored tiles.
+        pub emoji: bool,
+        /// The rows of the keyboard [ui] draws under the board, if any. See [keyboard].
+        pub keyboard: Option<[&'static str; 3]>,
+        /// Colors for tiles and keys, and emoji for results.
+        pub theme: Theme,
+        /// Reveal tiles, shake bad guesses and bounce wins in [ui].
+        pub animate: bool,
+    }
+
+    fn new_game(opts: &Opts) -> Result<Game, String> {
+        match opts.puzzle {
+            Some(number) => Game::daily(opts.word_len, number, opts.rules),
+            None if opts.actual_raw.is_empty() => Ok(Game::with_rules(
+                &DICT.rand_of_len(opts.word_len)?,
+                opts.rules,
+            )),
+            None => Ok(Game::with_rules(&opts.actual_raw, opts.rules)),
+        }
+    }
+
+    fn share(output: &mut dyn Write, opts: &Opts, game: &Game) {
+        if opts.share || opts.copy {
+            let block = opts.theme.recolor(&game.share(opts.ascii));
+            writeln!(output, "\n{}", block).unwrap();
+            if opts.copy {
+                crate::ui::term::copy(output, &block);
+            }
+        }
+    }
+
+    /// Records a finished game, returning a message to show if that fails.
+    fn save(opts: &Opts, game: &Game) -> Option<String> {
+        let record = Record::from_game(game);
+        let mut errors = Vec::new();
+        if let Some(Err(e)) = opts.stats.as_ref().map(|s| s.record(record.clone())) {
+            errors.push(format!("Couldn't save stats: {}", e));
+        }
+        if let Some(Err(e)) = opts.leaderboard.as_ref().map(|b| b.post(&record)) {
+            errors.push(format!("Couldn't post to the leaderboard: {}", e));
+        }
+
+        if errors.is_empty() {
+            None
+        } else {
+            Some(errors.join("\n"))
+        }
+    }
+
+    /// The recorded stats for games like this one, for the end-of-game screen.
+    fn stats_report(opts: &Opts, game: &Game) -> Option<String> {
+        let filter = Filter {
+            len: Some(game.len()),
+            mode: Some(if game.rules().hard {
+                Mode::Hard
+            } else {
+                Mode::Normal
+            }),
+        };
+        let records = opts.stats.as_ref()?.load().ok()?;
+        let these: Vec<&Record> = records.iter().filter(|r| filter.matches(r)).collect();
+
+        let mut out = Summary::of(these.iter().copied()).render(opts.ascii);
+        out.push_str(&Detail::of(&these).render());
+        Some(out)
+    }
+
+    pub const HINT_COMMAND: &str = ":hint";
+
+    fn time_limit_note(game: &Game) -> String {
+        match game.rules().time_limit {
+            Some(limit) => format!(" Time limit: {}s.", limit.as_secs()),
+            None => String::new(),
+        }
     }
 
     pub fn play(input: &mut dyn Read, output: &mut dyn Write, opts: Opts) -> Result<(), String> {
-        let actual = normalize(&opts.actual_raw);
-        writeln!(output, "Guess the word of length {}.", actual.len()).unwrap();
+        let mut game = new_game(&opts)?;
+        writeln!(
+            output,
+            "Guess the word of length {}{}. Type {} for a hint.{}",
+            game.len(),
+            if game.rules().hard {
+                " (hard mode)"
+            } else {
+                ""
+            },
+            HINT_COMMAND,
+            time_limit_note(&game)
+        )
+        .unwrap();
+        game.start_clock();
 
         let mut b = BufReader::new(input);
 
-        let mut guesses = 0;
         let mut tries = 0;
-        while guesses < 6 {
-            let guess = normalize(&read_trimmed(&mut b));
-            let prev_tries = tries;
-            if guess.len() != actual.len() {
-                writeln!(output, "Guess must be {} letters ", actual.len()).unwrap();
-                tries += 1;
-            } else if !DICT.has(&guess) {
-                writeln!(output, "Guess not in word list").unwrap();
-                tries += 1;
+        while game.guesses_remaining() > 0 {
+            let raw = read_trimmed(&mut b);
+            if raw.eq_ignore_ascii_case(HINT_COMMAND) {
+                match game.hint() {
+                    Ok(hint) => writeln!(output, "Hint: {}", hint).unwrap(),
+                    Err(msg) => writeln!(output, "{}", msg).unwrap(),
+                }
+                continue;
             }
 
-            if prev_tries != tries {
-                if tries >= 10 {
-                    return Err("Looks like you don't want to play.".to_string());
-                } else {
-                    continue;
-                };
-            }
+            let guess = normalize(&raw);
+            let checked = if guess.len() != game.len() {
+                Err(format!("Guess must be {} letters ", game.len()))
+            } else if !DICT.has(&guess) {
+                Err("Guess not in word list".to_string())
+            } else {
+                game.guess(&guess).map_err(|e| e.to_string())
+            };
 
-            guesses += 1;
+            let cmp = match checked {
+                Ok(cmp) => cmp,
+                Err(msg) => {
+                    writeln!(output, "{}", msg).unwrap();
+                    tries += 1;
+                    if tries >= 10 {
+                        return Err("Looks like you don't want to play.".to_string());
+                    }
+                    continue;
+                }
+            };
             tries = 0;
 
             for c in guess.chars() {
                 write!(output, "{} ", c).unwrap();
             }
             writeln!(output).unwrap();
-            let cmp = compare(&actual, &guess);
             // writeln!(output, "{}", cmp.into_iter().collect::<String>()).unwrap();
-            writeln!(output, "{}", join(&cmp, opts.ascii)).unwrap();
+            writeln!(output, "{}", opts.theme.recolor(&join(&cmp, opts.ascii))).unwrap();
         }
-        writeln!(output, "Answer: {}", actual).unwrap();
+        writeln!(output, "Answer: {}", game.actual).unwrap();
+        // A lost game's summary would just give the answer again.
+        if game.is_won() && game.hints_used() > 0 {
+            writeln!(output, "{}", game.summary()).unwrap();
+        } else if game.is_out_of_time() {
+            writeln!(output, "Out of time!").unwrap();
+        }
+        writeln!(output, "Time: {}", clock(game.elapsed())).unwrap();
+        if let Some(msg) = save(&opts, &game) {
+            writeln!(output, "{}", msg).unwrap();
+        }
+        share(output, &opts, &game);
 
         Ok(())
     }
@@ -277,6 +956,16 @@ pub mod wordl {
                 word_len: 0,
                 ascii: false,
                 actual_raw: "slump".to_string(),
+                rules: Rules::default(),
+                stats: None,
+                puzzle: None,
+                share: false,
+                copy: false,
+                leaderboard: None,
+                emoji: true,
+                keyboard: None,
+                theme: Theme::default(),
+                animate: false,
             },
         )
         .unwrap();
@@ -294,56 +983,441 @@ pub mod wordl {
 
             assert_eq!(line, expected_line);
         }
+
+        // Losing after a hint gives the answer once.
+        let mut input = Cursor::new(":hint\ntight\ntight\ntight\ntight\ntight\ntight\n");
+        let mut output = Cursor::new(vec![]);
+        play(
+            &mut input,
+            &mut output,
+            Opts {
+                word_len: 0,
+                ascii: false,
+                actual_raw: "slump".to_string(),
+                rules: Rules::default(),
+                stats: None,
+                puzzle: None,
+                share: false,
+                copy: false,
+                leaderboard: None,
+                emoji: true,
+                keyboard: None,
+                theme: Theme::default(),
+                animate: false,
+            },
+        )
+        .unwrap();
+        let output = String::from_utf8(output.into_inner()).unwrap();
+        assert_eq!(output.matches("SLUMP").count(), 1);
+    }
+
+    pub const HINT_KEY: char = '?';
+
+    /// The keyboard layouts [ui] can draw, and `none`.
+    pub const KEYBOARD_NAMES: [&str; 5] = ["qwerty", "qwertz", "azerty", "dvorak", "none"];
+
+    /// The letter rows of a layout in [KEYBOARD_NAMES].
+    pub fn keyboard(name: &str) -> Option<[&'static str; 3]> {
+        match name {
+            "qwerty" => Some(["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"]),
+            "qwertz" => Some(["QWERTZUIOP", "ASDFGHJKL", "YXCVBNM"]),
+            "azerty" => Some(["AZERTYUIOP", "QSDFGHJKLM", "WXCVBN"]),
+            "dvorak" => Some(["PYFGCRL", "AOEUIDHTNS", "QJKXBMWVZ"]),
+            _ => None,
+        }
     }
 
-    pub fn ui(opts: Opts) -> Result<(), String> {
-        use crate::ui::position::Position;
-        use crate::ui::term;
-        use crate::ui::term::Res;
+    /// Where each key goes, staggered like a real keyboard.
+    fn keys<'a>(
+        start: Position,
+        rows: &'a [&'a str; 3],
+    ) -> impl Iterator<Item = (Position, char)> + 'a {
+        rows.iter().enumerate().flat_map(move |(r, row)| {
+            row.chars()
+                .enumerate()
+                .map(move |(i, key)| (start + (r as i32 + 2 * i as i32, r as i32).into(), key))
+        })
+    }
+
+    /// Keys for mouse players, after the last row: each label, where it goes and what it does.
+    fn special_keys(start: Position, rows: &[&str; 3]) -> [(Position, &'static str, Res); 2] {
+        let last = rows.len() - 1;
+        let enter = start + ((last + 2 * rows[last].len() + 1) as i32, last as i32).into();
+        [
+            (enter, "ENTER", Res::Enter),
+            (enter + (6, 0).into(), "DEL", Res::Backspace),
+        ]
+    }
+
+    /// Draws each key colored by what the guesses so far say about its letter.
+    fn draw_keyboard(
+        screen: &mut Screen,
+        start: Position,
+        rows: &[&str; 3],
+        game: &Game,
+        theme: &Theme,
+    ) {
+        for (at, key) in keys(start, rows) {
+            let style = game
+                .letter_state(key)
+                .map_or_else(Default::default, |c| theme.tile(c));
+            screen.write_styled(&at, key, style);
+        }
+        for (at, label, _) in special_keys(start, rows) {
+            screen.writes(&at, label);
+        }
+    }
+
+    fn ui_clock(game: &Game) -> String {
+        let shown = match game.time_left() {
+            Some(left) => format!("Time left {}", clock(left)),
+            None => format!("Time {}", clock(game.elapsed())),
+        };
+        format!("{:<20}", shown)
+    }
+
+    pub fn ui(mut opts: Opts) -> Result<(), String> {
+        let played = {
+            let mut backend = Crossterm::new().map_err(|e| e.to_string())?;
+            ui_with(&mut backend, &mut opts)?
+        };
+
+        for game in &played {
+            share(&mut std::io::stdout(), &opts, game);
+        }
+        Ok(())
+    }
+
+    /// Plays [ui]'s games on `backend` until the player quits. Returns those with any guesses.
+    pub fn ui_with(backend: &mut dyn Backend, opts: &mut Opts) -> Result<Vec<Game>, String> {
+        let mut played = Vec::new();
+        loop {
+            let game = new_game(opts)?;
+            let (game, next) = ui_game(backend, game, opts, &played);
+            if 0 < game.guesses_made() {
+                played.push(game);
+            }
+            match next {
+                // Later games are random, at whatever length was picked.
+                Next::Again(len) => {
+                    opts.word_len = len;
+                    opts.actual_raw = String::new();
+                    opts.puzzle = None;
+                }
+                Next::Quit => break,
+            }
+        }
+        Ok(played)
+    }
 
-        let mut screen = term::default_screen();
-        term::make_room();
+    /// What to do once a game in [ui] is over.
+    enum Next {
+        Again(usize),
+        Quit,
+    }
 
-        let mut game = if 0 == opts.actual_raw.len() {
-            Game::with_len(opts.word_len)
+    /// Games, wins and guesses per win, shown after each game in [ui].
+    fn session_summary<'a>(games: impl IntoIterator<Item = &'a Game>) -> String {
+        let (mut played, mut won, mut guesses) = (0, 0, 0);
+        for game in games {
+            played += 1;
+            if game.is_won() {
+                won += 1;
+                guesses += game.guesses_made();
+            }
+        }
+        let per_win = if won == 0 {
+            "-".to_string()
         } else {
-            Game::new(&opts.actual_raw)
+            format!("{:.1}", guesses as f64 / won as f64)
         };
+        format!(
+            "This session: played {}, won {}, {} guesses per win.",
+            played, won, per_win
+        )
+    }
+
+    #[test]
+    fn test_session_summary() {
+        let mut won = Game::new("slump");
+        won.guess("maple").unwrap();
+        won.guess("slump").unwrap();
+        let lost = Game::with_rules(
+            "slump",
+            Rules {
+                guesses: 1,
+                ..Rules::default()
+            },
+        );
+        assert_eq!(
+            session_summary(&[won, lost]),
+            "This session: played 2, won 1, 2.0 guesses per win."
+        );
+        assert_eq!(
+            session_summary(&[]),
+            "This session: played 0, won 0, - guesses per win."
+        );
+    }
+
+    #[test]
+    fn test_ui() {
+        use crate::ui::backend::Memory;
+
+        let mut backend = Memory::typing((80, 24).into(), "maple\rslump\rq");
+        let mut opts = Opts {
+            word_len: 0,
+            ascii: false,
+            actual_raw: "slump".to_string(),
+            rules: Rules::default(),
+            stats: None,
+            puzzle: None,
+            share: false,
+            copy: false,
+            leaderboard: None,
+            emoji: true,
+            keyboard: keyboard("qwerty"),
+            theme: Theme::default(),
+            animate: false,
+        };
+        let played = ui_with(&mut backend, &mut opts).unwrap();
+        assert_eq!(played.len(), 1);
+        assert!(played[0].is_won());
+
+        // The clock makes the rest of the first and eighth lines vary.
+        let lines = backend.lines();
+        assert!(lines[0].starts_with("Guess the word of length 5."));
+        assert_eq!(lines[1], "MAPLE 🟨🟥🟨🟨🟥");
+        assert_eq!(lines[2], "SLUMP 🟩🟩🟩🟩🟩");
+        assert_eq!(lines[3], "_____");
+        assert!(lines[7].starts_with("You got it in 2 "));
+        assert_eq!(lines[9], "Q W E R T Y U I O P");
+        assert_eq!(lines[11], "  Z X C V B N M  ENTER DEL");
+        assert_eq!(
+            lines[13],
+            "This session: played 1, won 1, 2.0 guesses per win."
+        );
+        let tiles = backend.screen();
+        assert_eq!(tiles.style(&(0, 1).into()), opts.theme.tile(CLOSE));
+        assert_eq!(tiles.style(&(1, 2).into()), opts.theme.tile(MATCH));
+    }
+
+    /// Plays one game on a fresh screen, then asks what's next.
+    fn ui_game(
+        backend: &mut dyn Backend,
+        mut game: Game,
+        opts: &Opts,
+        played: &[Game],
+    ) -> (Game, Next) {
+        use crate::ui::animation::{Animation, Cell};
+        use crate::ui::screen::Style;
+        use crate::ui::term;
+
+        let mut screen = Screen::with_size(backend.size());
+        backend.clear().unwrap();
+
+        let lens = DICT.lens();
+        let mut len = game.len();
+        let mut next = Next::Quit;
 
         let prompt_start = Position::new(0, 0);
         let guesses_start = Position::new(0, prompt_start.row + 1);
         let guesses_end = guesses_start
             + Position::new(game.len() as i32 - 1, game.guesses_remaining() as i32 - 1);
         let err_start = Position::new(0, guesses_end.row + 1);
+        let keyboard_start = err_start + (0, 2).into();
+        let mut quit_at = match opts.keyboard {
+            Some(rows) => keyboard_start + (0, rows.len() as i32 + 1).into(),
+            None => keyboard_start,
+        };
+        let prompt = format!(
+            "Guess the word of length {}{}. Press {} for a hint.",
+            game.len(),
+            if game.rules().hard {
+                " (hard mode)"
+            } else {
+                ""
+            },
+            HINT_KEY,
+        );
+        let clock_at = prompt_start + Position::new(prompt.chars().count() as i32 + 2, 0);
+
+        // The prompt and clock can be cut off, but not the board and keyboard.
+        let emoji_cols = if opts.emoji { 1 + 2 * game.len() } else { 0 };
+        let keyboard_cols = opts.keyboard.map_or(0, |rows| {
+            let (at, label, _) = special_keys(keyboard_start, &rows)[1];
+            let widest = rows.iter().map(|r| 2 * r.len() + 1).max().unwrap_or(0);
+            widest.max(at.col as usize + label.len())
+        });
+        let needed = Position::new(
+            (game.len() + emoji_cols).max(keyboard_cols) as i32,
+            quit_at.row + 1,
+        );
+        let fits_in = |size: Position| needed.col <= size.col && needed.row <= size.row;
+        let too_small = format!(
+            "Terminal too small. Make it at least {}x{}, or press Ctrl-C to quit.",
+            needed.col, needed.row
+        );
+        let mut fits = fits_in(backend.size());
+        if !fits {
+            term::notice(backend, &too_small).unwrap();
+        }
+
         screen.writes(
             &prompt_start,
             &format!(
-                "\
-Guess the word of length {0}.
-{1}
-{1}
-{1}
-{1}
-{1}
-{1}
-
-",
-                game.len(),
-                "_".repeat(game.len()),
+                "{}\n{}\n",
+                prompt,
+                format!("{}\n", "_".repeat(game.len())).repeat(game.rules().guesses),
             ),
         );
+        let mut targets = Targets::default();
+        if let Some(rows) = &opts.keyboard {
+            draw_keyboard(&mut screen, keyboard_start, rows, &game, &opts.theme);
+            for (at, key) in keys(keyboard_start, rows) {
+                targets.add(at, at, Res::Write(key));
+            }
+            for (at, label, res) in special_keys(keyboard_start, rows) {
+                targets.add_text(at, label, res);
+            }
+        }
 
-        term::event_loop(|cursor, res| {
+        let menu =
+            |len: usize| format!("Enter: play again   -/+: word length {:<2}   q: quit", len);
+        let mut menu_at = quit_at;
+
+        /*
+         * Writes the summary, stats and what-next menu once the game is over, leaving `quit_at`
+         * below them and `menu_at` on the menu, which is made clickable.
+         */
+        let end = |screen: &mut Screen,
+                   game: &Game,
+                   quit_at: &mut Position,
+                   menu_at: &mut Position,
+                   targets: &mut Targets| {
+            screen.writes(&clock_at, &ui_clock(game));
+            screen.writes(&err_start, &format!("{}{}", game.summary(), " ".repeat(20)));
+            if let Some(msg) = save(opts, game) {
+                screen.writes(&(err_start + (0, 1).into()), &msg);
+            } else if let Some(report) = stats_report(opts, game) {
+                screen.writes(quit_at, &report);
+                quit_at.row += report.lines().count() as i32 + 1;
+            }
+            let session = session_summary(played.iter().chain(Some(game)));
+            screen.writes(quit_at, &session);
+            *menu_at = *quit_at + (0, 1).into();
+            let text = menu(game.len());
+            screen.writes(menu_at, &text);
+            for (label, res) in [
+                ("Enter: play again", Res::Enter),
+                ("-", Res::Write('-')),
+                ("+", Res::Write('+')),
+                ("q: quit", Res::Write('q')),
+            ] {
+                let col = text.find(label).unwrap() as i32;
+                targets.add_text(*menu_at + (col, 0).into(), label, res);
+            }
+            quit_at.row += 3;
+        };
+        let mut ended = false;
+        let mut animation: Option<Animation> = None;
+        let cells = |screen: &Screen, start: Position, len: usize| -> Vec<Cell> {
+            (0..len as i32)
+                .map(|i| start + (i, 0).into())
+                .map(|at| (at, screen.read(&at), screen.style(&at)))
+                .collect()
+        };
+
+        game.start_clock();
+        term::event_loop(backend, |backend, cursor, res| {
             let guess_start = guesses_start + Position::new(0, game.guesses_made() as i32);
             let guess_end = Position::new(guesses_end.col, guess_start.row);
 
+            // Draw everything again at the new size, or say it won't fit.
+            if let Res::Resize(size) = res {
+                screen.resize(size);
+                fits = fits_in(size);
+                if !fits {
+                    term::notice(backend, &too_small).unwrap();
+                    return Res::None;
+                }
+                backend.clear().unwrap();
+                backend.draw(&mut screen).unwrap();
+                let back_to = if ended {
+                    quit_at
+                } else {
+                    let typed = screen.reads(&guess_start, &guess_end).find('_');
+                    guess_start + Position::new(typed.unwrap_or(game.len() - 1) as i32, 0)
+                };
+                return Res::Move(back_to - cursor);
+            }
+            if !fits {
+                return match res {
+                    Res::Quit => Res::QuitAt(Position::new(0, 1)),
+                    _ => Res::None,
+                };
+            }
+
+            // A click means whatever's under it: a tile in this row, a key or a button.
+            let res = match res {
+                Res::Click(at)
+                    if !ended
+                        && at.row == guess_start.row
+                        && (0..game.len() as i32).contains(&at.col) =>
+                {
+                    Res::Move(at - cursor)
+                }
+                Res::Click(at) => targets.hit(at).unwrap_or(Res::None),
+                res => res,
+            };
+
+            // Animations play on ticks, and skip to the end for anything else.
+            if let Some(playing) = &mut animation {
+                let more = match res {
+                    Res::Tick => playing.step(&mut screen, Instant::now()),
+                    _ => {
+                        playing.finish(&mut screen);
+                        false
+                    }
+                };
+                if !more {
+                    animation = None;
+                }
+            }
+
             if game.guesses_remaining() < 1 {
-                return Res::QuitAt(
-                    err_start + (0, 2).into()
-                );
+                if !ended {
+                    // The clock ran out.
+                    ended = true;
+                    end(&mut screen, &game, &mut quit_at, &mut menu_at, &mut targets);
+                    backend.draw(&mut screen).unwrap();
+                    return Res::Move(quit_at - cursor);
+                }
+                let handled = match res {
+                    Res::Enter | Res::Write('r') => {
+                        next = Next::Again(len);
+                        Res::QuitAt(quit_at)
+                    }
+                    Res::Write('q') | Res::Quit => Res::QuitAt(quit_at),
+                    Res::Write(step @ ('-' | '+')) => {
+                        let i = lens.iter().position(|&l| l == len).unwrap_or(0);
+                        len = match step {
+                            '-' => lens[i.saturating_sub(1)],
+                            _ => lens[(i + 1).min(lens.len() - 1)],
+                        };
+                        screen.writes(&menu_at, &menu(len));
+                        Res::None
+                    }
+                    _ => Res::None,
+                };
+                backend.draw(&mut screen).unwrap();
+                return handled;
             }
 
             let handled = match res {
+                Res::Tick => {
+                    screen.writes(&clock_at, &ui_clock(&game));
+                    Res::None
+                }
                 Res::None => {
                     if cursor.col == 0 && cursor.row == 0 {
                         Res::Move((0, 1).into())
@@ -351,6 +1425,14 @@ Guess the word of length {0}.
                         Res::None
                     }
                 }
+                Res::Write(HINT_KEY) => {
+                    let msg = match game.hint() {
+                        Ok(hint) => format!("Hint: {}", hint),
+                        Err(msg) => msg,
+                    };
+                    screen.writes(&err_start, &format!("{}{}", &msg, &" ".repeat(20)));
+                    Res::None
+                }
                 Res::Write(ch) => {
                     screen.write(&cursor, ch.to_ascii_uppercase());
 
@@ -366,36 +1448,62 @@ Guess the word of length {0}.
                     let res = game.guess(&guess);
                     match res {
                         Ok(cmp) => {
-                            screen.writes(&(guess_end + (2, 0).into()), &join(&cmp, opts.ascii));
-                            screen.writes(&err_start, &format!("{}", " ".repeat(30)));
+                            let tiles: Vec<Cell> = guess
+                                .chars()
+                                .zip(&cmp)
+                                .enumerate()
+                                .map(|(i, (ch, &c))| {
+                                    let at = guess_start + Position::new(i as i32, 0);
+                                    (at, ch.to_ascii_uppercase(), opts.theme.tile(c))
+                                })
+                                .collect();
+                            for &(at, ch, style) in &tiles {
+                                screen.write_styled(&at, ch, style);
+                            }
+                            if opts.animate {
+                                let now = Instant::now();
+                                let mut playing = Animation::reveal(&tiles, Style::default(), now);
+                                if game.is_won() {
+                                    let above = guess_start - (0, 1).into();
+                                    let above = cells(&screen, above, tiles.len());
+                                    playing = playing.then(Animation::bounce(&tiles, &above, now));
+                                }
+                                playing.step(&mut screen, now);
+                                animation = Some(playing);
+                            }
+                            if opts.emoji {
+                                let row = opts.theme.recolor(&join(&cmp, opts.ascii));
+                                screen.writes(&(guess_end + (2, 0).into()), &row);
+                            }
+                            if let Some(rows) = &opts.keyboard {
+                                draw_keyboard(
+                                    &mut screen,
+                                    keyboard_start,
+                                    rows,
+                                    &game,
+                                    &opts.theme,
+                                );
+                            }
+                            screen.writes(&err_start, &" ".repeat(30));
 
                             if 0 < game.guesses_remaining() {
                                 Res::Move((-cursor.col, 1).into())
                             } else {
-                                if game.is_won() {
-                                    screen.writes(
-                                        &err_start,
-                                        &format!(
-                                            "You got it in {}! {}",
-                                            game.guesses_made(),
-                                            " ".repeat(20)
-                                        ),
-                                    );
-                                } else {
-                                    screen.writes(
-                                        &err_start,
-                                        &format!(
-                                            "The answer was {}.{}",
-                                            game.actual,
-                                            " ".repeat(20)
-                                        ),
-                                    );
-                                }
-                                Res::Move(err_start + (0, 2).into() - cursor)
+                                ended = true;
+                                end(&mut screen, &game, &mut quit_at, &mut menu_at, &mut targets);
+                                Res::Move(quit_at - cursor)
                             }
                         }
                         Err(msg) => {
                             screen.writes(&err_start, &format!("{}{}", &msg, &" ".repeat(20)));
+                            if opts.animate {
+                                let now = Instant::now();
+                                let row = cells(&screen, guess_start, game.len());
+                                let around = cells(&screen, guess_end + (1, 0).into(), 1);
+                                let mut playing = Animation::shake(&row, &around, now);
+                                playing.step(&mut screen, now);
+                                animation = Some(playing);
+                            }
                             Res::None
                         }
                     }
@@ -414,42 +1522,119 @@ Guess the word of length {0}.
                     (0 - cursor.col, 0).into(),
                     (guess_end.col - cursor.col, 0).into(),
                 )),
-                Res::Quit => {
-                    Res::QuitAt(err_start + (0, 2).into())
-                }
+                Res::Quit => Res::QuitAt(quit_at),
                 _ => res,
             };
-            term::just_dump_screen(&mut screen).unwrap();
+            backend.draw(&mut screen).unwrap();
             handled
         })
         .unwrap();
 
-        Ok(())
+        (game, next)
+    }
+
+    /// The keys [pick_profile] offers, in order. `0` is the shared default.
+    const PROFILE_KEYS: &str = "123456789abcdefghijklmnopqrstuvwxyz";
+
+    /**
+     * Asks who's playing. Returns the picked profile's name, or `None` for the shared default.
+     * Only the first 35 profiles get a key.
+     */
+    pub fn pick_profile(names: &[String]) -> Result<Option<String>, String> {
+        use crate::ui::term;
+
+        let mut backend = Crossterm::new().map_err(|e| e.to_string())?;
+        let mut screen = Screen::with_size(backend.size());
+        backend.clear().unwrap();
+
+        let mut menu = String::from("Who's playing?\n");
+        for (key, name) in PROFILE_KEYS.chars().zip(names) {
+            menu.push_str(&format!("  {}  {}\n", key, name));
+        }
+        menu.push_str("  0  (shared)\n");
+        screen.writes(&Position::new(0, 0), &menu);
+        let quit_at = Position::new(0, menu.lines().count() as i32 + 1);
+
+        let mut picked = None;
+        let mut quit = false;
+        term::event_loop(&mut backend, |backend, cursor, res| {
+            let handled = match res {
+                Res::Write('0') | Res::Enter => Res::QuitAt(quit_at),
+                Res::Write(ch) => match PROFILE_KEYS.find(ch.to_ascii_lowercase()) {
+                    Some(i) if i < names.len() => {
+                        picked = Some(names[i].clone());
+                        Res::QuitAt(quit_at)
+                    }
+                    _ => Res::None,
+                },
+                Res::Quit => {
+                    quit = true;
+                    Res::QuitAt(quit_at)
+                }
+                Res::None => Res::Move(quit_at - cursor),
+                _ => Res::None,
+            };
+            backend.draw(&mut screen).unwrap();
+            handled
+        })
+        .map_err(|e| e.to_string())?;
+
+        if quit {
+            return Err("No profile picked.".to_string());
+        }
+        Ok(picked)
     }
 }
 
 pub mod dicts {
     use std::fs::{File, OpenOptions};
     use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+    use std::sync::OnceLock;
 
     use lazy_static::lazy_static;
     use rand::seq::IteratorRandom;
 
-    static RAW_DICT: &str = include_str!("../dicts/singular");
+    /// The dictionaries `mkdict` makes, strictest first. The first is the default.
+    pub const NAMES: [&str; 3] = ["singular", "improper", "loose"];
+    static RAW_DICTS: [&str; 3] = [
+        include_str!("../dicts/singular"),
+        include_str!("../dicts/improper"),
+        include_str!("../dicts/loose"),
+    ];
+    static CHOSEN: OnceLock<usize> = OnceLock::new();
     lazy_static! {
         pub static ref DICT: Dict = Dict::new();
     }
 
+    /// Picks the dictionary [DICT] is made from. Only works before anything has used it.
+    pub fn choose(name: &str) -> Result<(), String> {
+        let i = NAMES
+            .iter()
+            .position(|&n| n == name)
+            .ok_or_else(|| format!("Unknown dictionary {:?}", name))?;
+        CHOSEN
+            .set(i)
+            .map_err(|_| "The dictionary has already been chosen.".to_string())
+    }
+
+    /// The name of the dictionary [DICT] is made from.
+    pub fn chosen() -> &'static str {
+        NAMES[*CHOSEN.get_or_init(|| 0)]
+    }
+
     pub fn dict_words() -> Vec<String> {
         let mut res: Vec<String> = Vec::new();
 
-        for line_r in RAW_DICT.lines() {
+        for line_r in RAW_DICTS[*CHOSEN.get_or_init(|| 0)].lines() {
             let line_s = line_r.trim().to_string();
             if !line_s.is_empty() {
                 res.push(line_s.to_uppercase());
             }
         }
 
+        // Proper nouns sort apart from the rest until uppercased.
+        res.sort();
+        res.dedup();
         res
     }
 
@@ -478,14 +1663,40 @@ pub mod dicts {
                 .collect()
         }
 
-        pub fn rand_of_len(&self, len: usize) -> String {
+        /// Every word length there are words of, shortest first.
+        pub fn lens(&self) -> Vec<usize> {
+            let mut lens: Vec<usize> = self.words.iter().map(|w| w.len()).collect();
+            lens.sort_unstable();
+            lens.dedup();
+            lens
+        }
+
+        pub fn rand_of_len(&self, len: usize) -> Result<String, String> {
             let mut rng = rand::thread_rng();
-            self.word_lens(len).into_iter().choose(&mut rng).unwrap()
+            self.word_lens(len)
+                .into_iter()
+                .choose(&mut rng)
+                .ok_or_else(|| no_words(len))
         }
 
         pub fn has(&self, word: &str) -> bool {
             self.words.binary_search(&word.to_string()).is_ok()
         }
+
+        /// The word for daily puzzle `number`, scattered so neighbouring days aren't alike.
+        pub fn daily(&self, len: usize, number: u32) -> Result<String, String> {
+            let words = self.word_lens(len);
+            if words.is_empty() {
+                return Err(no_words(len));
+            }
+            let scattered =
+                (number as u64 ^ (len as u64) << 32).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+            Ok(words[(scattered % words.len() as u64) as usize].clone())
+        }
+    }
+
+    fn no_words(len: usize) -> String {
+        format!("There are no {}-letter words to play.", len)
     }
 
     /**
@@ -603,8 +1814,213 @@ pub mod dicts {
 
     #[test]
     fn test_rand() {
-        let w = crate::dicts::DICT.rand_of_len(5);
+        let w = crate::dicts::DICT.rand_of_len(5).unwrap();
         println!("W: {:?}", w);
-        assert!(w.len() == 5)
+        assert!(w.len() == 5);
+        assert!(crate::dicts::DICT.rand_of_len(30).is_err());
+    }
+}
+
+/**
+ * Challenge codes: a word and its rules packed into something like `3MZ8KQ0WT7D`, so the answer
+ * isn't left in a colleague's shell history. Not encryption, just enough to not read at a glance.
+ */
+pub mod challenge {
+    use crate::dicts::DICT;
+    use crate::wordl::{normalize, Rules};
+
+    /// Crockford's base 32, which leaves out easily confused letters.
+    const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+    const VERSION: u128 = 1;
+    pub const MAX_LEN: usize = 15;
+    pub const MAX_GUESSES: usize = 31;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Challenge {
+        pub word: String,
+        pub rules: Rules,
+    }
+
+    /**
+     * Layout, least significant bits first: an 8-bit random salt, then everything else XORed with
+     * a keystream from the salt: version (2), hard (1), guesses (5), length (4), 5 bits per
+     * letter, and an 8-bit checksum of what came before it.
+     */
+    pub fn create(word: &str, rules: Rules) -> Result<String, String> {
+        let word = normalize(word.trim());
+        if !DICT.has(&word) {
+            return Err(format!("{:?} is not in the word list!", word));
+        }
+        if MAX_LEN < word.len() {
+            return Err(format!("Words can be at most {} letters.", MAX_LEN));
+        }
+        if !(1..=MAX_GUESSES).contains(&rules.guesses) {
+            return Err(format!("Guesses must be from 1 to {}.", MAX_GUESSES));
+        }
+
+        Ok(encode(rand::random(), &word, rules))
+    }
+
+    pub fn decode(code: &str) -> Result<Challenge, String> {
+        let bad = || format!("{:?} is not a valid challenge code.", code);
+
+        let mut value: u128 = 0;
+        let mut digits = 0;
+        for ch in code.chars().filter(|&c| c != '-' && !c.is_whitespace()) {
+            let digit = match ch.to_ascii_uppercase() {
+                'O' => 0,
+                'I' | 'L' => 1,
+                c => ALPHABET
+                    .iter()
+                    .position(|&a| a as char == c)
+                    .ok_or_else(bad)?,
+            };
+            if 25 <= digits {
+                return Err(bad());
+            }
+            value |= (digit as u128) << (5 * digits);
+            digits += 1;
+        }
+
+        if 5 * digits < 28 {
+            return Err(bad());
+        }
+        let salt = (value & 0xff) as u8;
+        let mut body = Bits {
+            value: (value >> 8) ^ (keystream(salt) & mask(5 * digits - 8)),
+            len: 0,
+        };
+
+        let version = body.pop(2);
+        let hard = body.pop(1) == 1;
+        let guesses = body.pop(5) as usize;
+        let len = body.pop(4) as usize;
+        if version != VERSION || 5 * digits < 28 + 5 * len {
+            return Err(bad());
+        }
+        // Nothing [create] would make, and nothing that could be played.
+        if len == 0 || !(1..=MAX_GUESSES).contains(&guesses) {
+            return Err(bad());
+        }
+        let word: String = (0..len)
+            .map(|_| (b'A' + body.pop(5) as u8) as char)
+            .collect();
+
+        let check = checksum(body.value & mask(body.len));
+        if body.pop(8) != check as u128 || !word.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(bad());
+        }
+
+        Ok(Challenge {
+            word,
+            rules: Rules {
+                guesses,
+                hard,
+                ..Rules::default()
+            },
+        })
+    }
+
+    /// Bits appended at the top, or read from the bottom.
+    #[derive(Default)]
+    struct Bits {
+        value: u128,
+        len: usize,
+    }
+
+    impl Bits {
+        fn push(&mut self, v: u128, bits: usize) {
+            self.value |= v << self.len;
+            self.len += bits;
+        }
+
+        fn pop(&mut self, bits: usize) -> u128 {
+            let v = self.value >> self.len & mask(bits);
+            self.len += bits;
+            v
+        }
+    }
+
+    fn mask(bits: usize) -> u128 {
+        (1 << bits) - 1
+    }
+
+    fn encode(salt: u8, word: &str, rules: Rules) -> String {
+        let mut body = Bits::default();
+        body.push(VERSION, 2);
+        body.push(rules.hard as u128, 1);
+        body.push(rules.guesses as u128, 5);
+        body.push(word.len() as u128, 4);
+        for b in word.bytes() {
+            body.push((b - b'A') as u128, 5);
+        }
+        body.push(checksum(body.value) as u128, 8);
+
+        let digits = (8 + body.len).div_ceil(5);
+        let value = salt as u128 | (body.value ^ keystream(salt) & mask(5 * digits - 8)) << 8;
+        (0..digits)
+            .map(|i| ALPHABET[(value >> (5 * i) & 0x1f) as usize] as char)
+            .collect()
+    }
+
+    /// SplitMix64, twice over.
+    fn keystream(salt: u8) -> u128 {
+        let mut state = salt as u64;
+        let mut next = || {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        next() as u128 | (next() as u128) << 64
+    }
+
+    fn checksum(value: u128) -> u8 {
+        value
+            .to_le_bytes()
+            .iter()
+            .fold(0x45u8, |h, &b| h.rotate_left(3) ^ b)
+    }
+
+    #[test]
+    fn test_challenge() {
+        let rules = Rules {
+            guesses: 4,
+            hard: true,
+            ..Rules::default()
+        };
+        let code = create("slump", rules).unwrap();
+        assert_eq!(code.len(), 11);
+        assert!(!code.contains("SLUMP"));
+        assert_eq!(
+            decode(&code.to_lowercase()),
+            Ok(Challenge {
+                word: "SLUMP".to_string(),
+                rules
+            })
+        );
+
+        assert_ne!(encode(1, "SLUMP", rules), encode(2, "SLUMP", rules));
+        let mut typo = encode(1, "SLUMP", rules).into_bytes();
+        typo[5] = if typo[5] == b'Z' { b'Y' } else { b'Z' };
+        assert!(decode(std::str::from_utf8(&typo).unwrap()).is_err());
+        assert!(decode("hello!").is_err());
+        let unplayable = Rules {
+            guesses: 0,
+            ..rules
+        };
+        assert!(decode(&encode(1, "SLUMP", unplayable)).is_err());
+        assert!(decode(&encode(1, "", rules)).is_err());
+
+        assert!(create("zzzzz", rules).is_err());
+        assert!(create(
+            "slump",
+            Rules {
+                guesses: 40,
+                ..rules
+            }
+        )
+        .is_err());
     }
 }
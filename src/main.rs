@@ -1,8 +1,25 @@
-use clap::Parser;
+use std::fs::File;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use clap::{ArgMatches, FromArgMatches, IntoApp, Parser, Subcommand};
 use supports_unicode::Stream;
 
-use wordle_rs::dicts;
-use wordle_rs::wordl::{play, ui, Opts};
+use wordle_rs::config::effective::{Settings, Source};
+use wordle_rs::config::file;
+use wordle_rs::engine::host;
+use wordle_rs::solve::{matrix, reverse, strategy};
+use wordle_rs::stats::breakdown::{self, By};
+use wordle_rs::stats::exchange::{self, Format};
+use wordle_rs::stats::leaderboard::{self, Board};
+use wordle_rs::stats::profile::{Profile, Profiles};
+use wordle_rs::stats::store::Store;
+use wordle_rs::stats::summary::{self, Filter, Mode};
+use wordle_rs::ui::theme::{self, Depth, Theme};
+use wordle_rs::wordl::{self, play, ui, Opts, Rules, MAX_GUESSES};
+use wordle_rs::{challenge, dicts};
 
 fn main() {
     match cli() {
@@ -17,6 +34,7 @@ struct Args {
     #[clap(
         short = 'n',
         long,
+        global = true,
         default_value_t = 5,
         help = "I dare you to try 2- or 10-letter words."
     )]
@@ -32,6 +50,7 @@ struct Args {
     #[clap(
         short = 'w',
         long,
+        global = true,
         help = "The word you want to guess. (Maybe you set this up for someone else? Just testing things out?)"
     )]
     word: Option<String>,
@@ -39,6 +58,7 @@ struct Args {
     #[clap(
         short,
         long,
+        global = true,
         help = "\
 ASCII mode. Good for windows or if you're color blind like me :D
     x : Miss (not in word)
@@ -48,19 +68,406 @@ ASCII mode. Good for windows or if you're color blind like me :D
     )]
     ascii: bool,
 
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     unicode: bool,
 
+    #[clap(
+        short = 'g',
+        long,
+        global = true,
+        default_value_t = MAX_GUESSES,
+        validator = at_least_one,
+        help = "How many guesses you get."
+    )]
+    guesses: usize,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Hard mode. Any revealed hints must be used in later guesses."
+    )]
+    hard: bool,
+
+    #[clap(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help = "Speedrun mode. You lose if the clock runs out."
+    )]
+    time_limit: Option<u64>,
+
+    #[clap(
+        long,
+        conflicts_with = "word",
+        help = "Play today's puzzle, the same word as everyone else."
+    )]
+    daily: bool,
+
+    #[clap(
+        long,
+        conflicts_with_all = &["word", "daily"],
+        help = "Play a challenge code from `wordle-rs challenge create`."
+    )]
+    challenge: Option<String>,
+
+    #[clap(
+        long,
+        help = "Print a spoiler-free result grid to share when you're done."
+    )]
+    share: bool,
+
+    #[clap(
+        long,
+        help = "Copy the result grid to the clipboard too, if your terminal allows it."
+    )]
+    copy: bool,
+
+    #[clap(
+        long,
+        help = "Only color the letters of each guess, without the emoji column beside them."
+    )]
+    no_emoji: bool,
+
+    #[clap(
+        long,
+        help = "Don't animate tiles. Animations are also off when output isn't a terminal."
+    )]
+    no_animation: bool,
+
+    #[clap(
+        long,
+        default_value = wordl::KEYBOARD_NAMES[0],
+        possible_values = wordl::KEYBOARD_NAMES,
+        help = "The keyboard drawn under the board, showing what's known about each letter."
+    )]
+    keyboard: String,
+
+    #[clap(
+        long,
+        global = true,
+        default_value = theme::NAMES[0],
+        possible_values = theme::NAMES,
+        help = "Colors for tiles and keys, and emoji for results. high-contrast is orange and blue; custom takes [colors] from the config file."
+    )]
+    theme: String,
+
+    #[clap(
+        long,
+        global = true,
+        default_value = dicts::NAMES[0],
+        possible_values = dicts::NAMES,
+        help = "Which words can be picked and guessed. Looser ones include proper nouns and plurals."
+    )]
+    dict: String,
+
+    #[clap(
+        long,
+        global = true,
+        env = LEADERBOARD_ENV,
+        help = "A leaderboard file shared with your team. Daily results are posted to it."
+    )]
+    leaderboard: Option<PathBuf>,
+
+    #[clap(
+        long,
+        global = true,
+        env = PROFILE_ENV,
+        help = "Play as this profile, with its own stats and config. Asks who's playing if there are profiles and this isn't given."
+    )]
+    profile: Option<String>,
+
     // --
     #[clap(short, long, hide = true)]
     mkdict: bool,
 
     #[clap(short = 'i', long, hide = true)]
     ui: bool,
+
+    #[clap(long, hide = true)]
+    mkmatrix: bool,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[clap(about = "Inspect the config file, which supplies defaults for the options above.")]
+    Config {
+        #[clap(subcommand)]
+        action: ConfigAction,
+    },
+
+    #[clap(about = "Manage the profiles players keep their own stats and config under.")]
+    Profile {
+        #[clap(subcommand)]
+        action: ProfileAction,
+    },
+
+    #[clap(about = "Set a word for someone else without giving it away.")]
+    Challenge {
+        #[clap(subcommand)]
+        action: ChallengeAction,
+    },
+
+    #[clap(about = "Paste a shared result grid to see which guesses could have produced it.")]
+    Reverse {
+        #[clap(
+            help = "The answer, if you know it. Otherwise lists the answers that best fit the grid."
+        )]
+        answer: Option<String>,
+    },
+
+    #[clap(about = "Play against a bot speaking the wordle-rs protocol on stdin/stdout.")]
+    HostBot {
+        #[clap(long, default_value_t = 1, help = "How many games to play.")]
+        games: usize,
+
+        #[clap(
+            required = true,
+            last = true,
+            help = "The bot's command line, after --."
+        )]
+        bot: Vec<String>,
+    },
+
+    #[clap(about = "Show your win rate, streaks and guess distribution.")]
+    Stats {
+        #[clap(long, help = "Only count games with words of this length.")]
+        len: Option<usize>,
+
+        #[clap(long, possible_values = Mode::NAMES, help = "Only count games played this way.")]
+        mode: Option<String>,
+
+        #[clap(
+            long,
+            possible_values = By::NAMES,
+            help = "Break down by word length and mode, or by mode alone."
+        )]
+        by: Option<String>,
+    },
+
+    #[clap(about = "Rank everyone on the leaderboard for a daily puzzle or the past week.")]
+    Leaderboard {
+        #[clap(
+            long,
+            help = "The puzzle played on this date (YYYY-MM-DD). Defaults to today."
+        )]
+        day: Option<String>,
+
+        #[clap(
+            long,
+            conflicts_with = "day",
+            help = "Totals over the past seven puzzles."
+        )]
+        week: bool,
+    },
+
+    #[clap(about = "Dump every recorded game, with each guess and its pattern.")]
+    Export {
+        #[clap(long, default_value = "csv", possible_values = Format::NAMES)]
+        format: String,
+
+        #[clap(long, help = "Only games played on or after this date (YYYY-MM-DD).")]
+        since: Option<String>,
+    },
+
+    #[clap(
+        about = "Merge in games exported from another machine, skipping ones already recorded."
+    )]
+    Import {
+        #[clap(help = "A CSV or JSON export, or another stats file. Reads stdin if not given.")]
+        file: Option<PathBuf>,
+    },
+
+    #[clap(about = "Watch a built-in strategy play.")]
+    Autoplay {
+        #[clap(
+            short,
+            long,
+            default_value = "entropy",
+            possible_values = strategy::NAMES
+        )]
+        strategy: String,
+    },
+
+    #[clap(about = "See how well a built-in strategy does over many games.")]
+    Bench {
+        #[clap(
+            short,
+            long,
+            default_value = "entropy",
+            possible_values = strategy::NAMES
+        )]
+        strategy: String,
+
+        #[clap(long, default_value_t = 100, help = "How many games to play.")]
+        games: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    #[clap(about = "Print the effective settings and where each came from.")]
+    Show,
+}
+
+#[derive(Subcommand, Debug)]
+enum ProfileAction {
+    #[clap(about = "List the profiles.")]
+    List,
+
+    #[clap(about = "Add a profile.")]
+    Create { name: String },
+
+    #[clap(about = "Rename a profile, keeping its stats and config.")]
+    Rename { from: String, to: String },
+
+    #[clap(about = "Delete a profile along with its stats and config.")]
+    Delete {
+        name: String,
+
+        #[clap(long, help = "Don't ask first.")]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ChallengeAction {
+    #[clap(
+        about = "Turn a word, with the -g and --hard rules, into a code. Asks for the word unless -w is given."
+    )]
+    Create,
+}
+
+const LEADERBOARD_ENV: &str = "WORDLE_RS_LEADERBOARD";
+const PROFILE_ENV: &str = "WORDLE_RS_PROFILE";
+
+fn at_least_one(value: &str) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("Must be at least 1.".to_string()),
+        Ok(_) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Fills in whatever wasn't given on the command line from the config file.
+fn configure(
+    matches: &ArgMatches,
+    args: &mut Args,
+    profile: Option<&Profile>,
+) -> Result<(Settings, Theme), String> {
+    let config = match file::path(profile) {
+        Some(path) => file::load(&path)?,
+        None => file::Config::default(),
+    };
+    let given = |id: &str| 0 < matches.occurrences_of(id);
+    let mut settings = Settings::default();
+
+    args.word_len = settings.pick(
+        "word-len",
+        given("word-len"),
+        args.word_len,
+        config.word_len,
+    );
+    args.inline = settings.pick("inline", given("inline"), args.inline, config.inline);
+    // -a or -u on the command line overrides both from the config file.
+    let charset = given("ascii") || given("unicode");
+    args.ascii = settings.pick("ascii", charset, args.ascii, config.ascii);
+    args.unicode = settings.pick("unicode", charset, args.unicode, config.unicode);
+    args.guesses = settings.pick("guesses", given("guesses"), args.guesses, config.guesses);
+    args.hard = settings.pick("hard", given("hard"), args.hard, config.hard);
+    args.time_limit = settings.pick_option(
+        "time-limit",
+        given("time-limit"),
+        args.time_limit,
+        config.time_limit,
+    );
+    args.dict = settings.pick("dict", given("dict"), args.dict.clone(), config.dict);
+    args.share = settings.pick("share", given("share"), args.share, config.share);
+    args.copy = settings.pick("copy", given("copy"), args.copy, config.copy);
+    args.no_emoji = settings.pick(
+        "no-emoji",
+        given("no-emoji"),
+        args.no_emoji,
+        config.no_emoji,
+    );
+    args.no_animation = settings.pick(
+        "no-animation",
+        given("no-animation"),
+        args.no_animation,
+        config.no_animation,
+    );
+    args.keyboard = settings.pick(
+        "keyboard",
+        given("keyboard"),
+        args.keyboard.clone(),
+        config.keyboard,
+    );
+
+    let source = if given("leaderboard") {
+        Source::Cli
+    } else if args.leaderboard.is_some() {
+        Source::Env(LEADERBOARD_ENV)
+    } else if config.leaderboard.is_some() {
+        args.leaderboard = config.leaderboard;
+        Source::File
+    } else {
+        Source::Default
+    };
+    settings.record(
+        "leaderboard",
+        args.leaderboard
+            .as_ref()
+            .map_or("none".to_string(), |p| p.display().to_string()),
+        source,
+    );
+
+    dicts::choose(&args.dict)?;
+    // The command line is checked by clap, but not the config file.
+    at_least_one(&args.guesses.to_string()).map_err(|e| format!("guesses: {}", e))?;
+    if !wordl::KEYBOARD_NAMES.contains(&args.keyboard.as_str()) {
+        return Err(format!("Unknown keyboard {:?}", args.keyboard));
+    }
+
+    // NO_COLOR (https://no-color.org) beats the config file, but not the command line.
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if !given("theme") && no_color {
+        args.theme = "mono".to_string();
+        settings.record("theme", args.theme.clone(), Source::Env("NO_COLOR"));
+    } else {
+        args.theme = settings.pick("theme", given("theme"), args.theme.clone(), config.theme);
+    }
+    let colors = config.colors.as_ref().map(|c| c.rgb()).transpose()?;
+    let theme = Theme::named(&args.theme, colors, Depth::detect())?;
+
+    Ok((settings, theme))
 }
 
 fn cli() -> Result<(), String> {
-    let args = Args::parse();
+    let matches = Args::into_app().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    let profiles = Profiles::default_root().map(|root| Profiles::at(&root));
+    let profile = match (&profiles, &args.profile) {
+        (Some(profiles), Some(name)) => Some(profiles.existing(name)?),
+        (None, Some(_)) => return Err("No data directory to keep profiles in.".to_string()),
+        // Only ask when about to start an interactive game.
+        (Some(profiles), None)
+            if args.command.is_none() && !args.inline && !args.mkdict && !args.mkmatrix =>
+        {
+            let names = profiles.list()?;
+            if names.is_empty() {
+                None
+            } else {
+                wordl::pick_profile(&names)?
+                    .map(|name| profiles.existing(&name))
+                    .transpose()?
+            }
+        }
+        _ => None,
+    };
+    let (settings, theme) = configure(&matches, &mut args, profile.as_ref())?;
 
     let ascii = if args.unicode {
         false
@@ -70,17 +477,230 @@ fn cli() -> Result<(), String> {
         !supports_unicode::on(Stream::Stdout)
     };
 
-    let (actual_raw, word_len) = if let Some(w) = args.word {
+    let challenge = args
+        .challenge
+        .as_deref()
+        .map(challenge::decode)
+        .transpose()?;
+    let mut rules = match &challenge {
+        Some(c) => c.rules,
+        None => Rules {
+            guesses: args.guesses,
+            hard: args.hard,
+            ..Rules::default()
+        },
+    };
+    rules.time_limit = args.time_limit.map(Duration::from_secs);
+
+    let stats = match &profile {
+        Some(profile) => Some(profile.stats()),
+        None => Store::default_path().map(|p| Store::at(&p)),
+    };
+    let player = match &profile {
+        Some(profile) => profile.name().to_string(),
+        None => leaderboard::whoami(),
+    };
+    let board = args.leaderboard.as_ref().map(|p| Board::at(p, &player));
+
+    if let Some(command) = args.command {
+        return match command {
+            Command::Config {
+                action: ConfigAction::Show,
+            } => {
+                match &profile {
+                    Some(profile) => println!("Profile: {}", profile.name()),
+                    None => println!("Profile: none"),
+                }
+                match file::path(profile.as_ref()) {
+                    Some(path) if path.exists() => println!("Config file: {}", path.display()),
+                    Some(path) => println!("Config file: {} (not found)", path.display()),
+                    None => println!("Config file: none"),
+                }
+                print!("{}", settings.render());
+                Ok(())
+            }
+            Command::Profile { action } => {
+                let profiles = profiles.ok_or("No data directory to keep profiles in.")?;
+                match action {
+                    ProfileAction::List => {
+                        for name in profiles.list()? {
+                            println!("{}", name);
+                        }
+                    }
+                    ProfileAction::Create { name } => {
+                        let profile = profiles.create(&name)?;
+                        println!("Created {} in {}", name, profile.dir().display());
+                    }
+                    ProfileAction::Rename { from, to } => {
+                        profiles.rename(&from, &to)?;
+                        println!("Renamed {} to {}.", from, to);
+                    }
+                    ProfileAction::Delete { name, yes } => {
+                        profiles.existing(&name)?;
+                        if !yes {
+                            eprint!("Delete {} and all their stats? [y/N] ", name);
+                            let mut line = String::new();
+                            std::io::stdin()
+                                .read_line(&mut line)
+                                .map_err(|e| e.to_string())?;
+                            if !line.trim().eq_ignore_ascii_case("y") {
+                                return Err("Not deleted.".to_string());
+                            }
+                        }
+                        profiles.delete(&name)?;
+                        println!("Deleted {}.", name);
+                    }
+                }
+                Ok(())
+            }
+            Command::Challenge {
+                action: ChallengeAction::Create,
+            } => {
+                let word = match args.word {
+                    Some(w) => w,
+                    None => {
+                        eprint!("Word to challenge with: ");
+                        let mut line = String::new();
+                        std::io::stdin()
+                            .read_line(&mut line)
+                            .map_err(|e| e.to_string())?;
+                        line
+                    }
+                };
+                let code = challenge::create(&word, rules)?;
+                println!("{}", code);
+                eprintln!("Play it with: wordle-rs --challenge {}", code);
+                Ok(())
+            }
+            Command::Reverse { answer } => {
+                reverse::reverse(&mut std::io::stdin(), &mut std::io::stdout(), answer, ascii)
+            }
+            Command::HostBot { games, bot } => {
+                host::host_bot(&bot, games, args.word_len, args.word, rules)
+            }
+            Command::Stats { len, mode, by } => {
+                let store = stats.ok_or("No data directory to keep stats in.")?;
+                let filter = Filter {
+                    len,
+                    mode: mode.map(|m| m.parse()).transpose()?,
+                };
+                match by {
+                    Some(by) => print!("{}", breakdown::report(&store, filter, by.parse()?)?),
+                    None => print!("{}", summary::report(&store, filter, ascii)?),
+                }
+                Ok(())
+            }
+            Command::Leaderboard { day, week } => {
+                let board = board
+                    .ok_or("No leaderboard. Pass --leaderboard or set WORDLE_RS_LEADERBOARD.")?;
+                // Each dictionary has its own daily words, so only compare like with like.
+                let mut entries = board.load()?;
+                entries.retain(|e| e.dict == dicts::chosen());
+                let puzzle = match day {
+                    Some(day) => wordl::puzzle_number(
+                        NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+                            .map_err(|e| format!("Bad date {:?}: {}", day, e))?,
+                    ),
+                    None => wordl::todays_puzzle(),
+                };
+                if week {
+                    let from = puzzle.saturating_sub(6);
+                    let ranked = leaderboard::span(&entries, from, puzzle, args.word_len);
+                    print!("{}", leaderboard::render_span(&ranked, from, puzzle));
+                } else {
+                    let ranked = leaderboard::day(&entries, puzzle, args.word_len);
+                    print!("{}", leaderboard::render_day(&ranked, puzzle));
+                }
+                Ok(())
+            }
+            Command::Export { format, since } => {
+                let store = stats.ok_or("No data directory to keep stats in.")?;
+                exchange::export(
+                    &mut std::io::stdout(),
+                    &store.load()?,
+                    format.parse()?,
+                    since.as_deref(),
+                )
+            }
+            Command::Import { file } => {
+                let store = stats.ok_or("No data directory to keep stats in.")?;
+                let records = match file {
+                    Some(path) => exchange::parse(
+                        &mut File::open(&path).map_err(|e| format!("{}: {}", path.display(), e))?,
+                    )?,
+                    None => exchange::parse(&mut std::io::stdin())?,
+                };
+                let total = records.len();
+                let added = exchange::import(&store, records)?;
+                println!(
+                    "Imported {} new games ({} already recorded).",
+                    added,
+                    total - added
+                );
+                Ok(())
+            }
+            Command::Autoplay { strategy: name } => {
+                let mut strategy = strategy::by_name(&name).unwrap();
+                let answer = match args.word {
+                    Some(w) => w,
+                    None => dicts::DICT.rand_of_len(args.word_len)?,
+                };
+                strategy::watch(
+                    &mut std::io::stdout(),
+                    strategy.as_mut(),
+                    &answer,
+                    rules,
+                    ascii,
+                )
+            }
+            Command::Bench {
+                strategy: name,
+                games,
+            } => {
+                let word_len = args.word_len;
+                strategy::check_len(word_len)?;
+                let mut strategy = strategy::by_name(&name).unwrap();
+                let answers = (0..games)
+                    .map(|_| dicts::DICT.rand_of_len(word_len))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let score = strategy::benchmark(strategy.as_mut(), &answers, rules, |_| {})?;
+                println!(
+                    "{} won {} of {} ({:.0}%), averaging {:.2} guesses.",
+                    name,
+                    score.won,
+                    score.played,
+                    100.0 * score.won as f64 / score.played.max(1) as f64,
+                    score.average(),
+                );
+                Ok(())
+            }
+        };
+    }
+
+    let puzzle = if args.daily {
+        Some(wordl::todays_puzzle())
+    } else {
+        None
+    };
+
+    let (actual_raw, word_len) = if let Some(c) = challenge {
+        let len = c.word.len();
+        (c.word, len)
+    } else if let Some(w) = args.word {
         (w.clone(), w.len())
+    } else if puzzle.is_some() {
+        (String::new(), args.word_len)
     } else {
         (
-            wordle_rs::dicts::DICT.rand_of_len(args.word_len),
+            wordle_rs::dicts::DICT.rand_of_len(args.word_len)?,
             args.word_len,
         )
     };
 
     if args.mkdict {
         dicts::mkdict()
+    } else if args.mkmatrix {
+        matrix::mkmatrix(word_len)
     } else if args.inline {
         play(
             &mut std::io::stdin(),
@@ -89,6 +709,16 @@ fn cli() -> Result<(), String> {
                 word_len,
                 actual_raw,
                 ascii,
+                rules,
+                stats,
+                puzzle,
+                share: args.share,
+                copy: args.copy,
+                leaderboard: board,
+                emoji: !args.no_emoji,
+                keyboard: None,
+                theme,
+                animate: false,
             },
         )
     } else {
@@ -96,6 +726,16 @@ fn cli() -> Result<(), String> {
             word_len,
             actual_raw,
             ascii,
+            rules,
+            stats,
+            puzzle,
+            share: args.share,
+            copy: args.copy,
+            leaderboard: board,
+            emoji: !args.no_emoji,
+            keyboard: wordl::keyboard(&args.keyboard),
+            theme,
+            animate: !args.no_animation && std::io::stdout().is_terminal(),
         })
     }
 }
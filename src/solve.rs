@@ -0,0 +1,1007 @@
+pub mod pattern {
+    use crate::wordl::{CLOSE, MATCH, MISS};
+
+    /// The feedback for one guess, packed as base-3 digits with the first letter in the
+    /// least significant digit.
+    pub type Pattern = u16;
+
+    /// The longest word whose feedback fits in a [Pattern].
+    pub const MAX_LEN: usize = 10;
+
+    const DIGITS: [char; 3] = [MISS, CLOSE, MATCH];
+
+    /**
+     * Allocation-free equivalent of [crate::wordl::compare] for normalized (uppercase ASCII)
+     * words of equal length, no longer than [MAX_LEN].
+     */
+    pub fn of(actual: &[u8], guess: &[u8]) -> Pattern {
+        let mut unused = [0u8; 32];
+        let mut digits = [0u8; MAX_LEN];
+
+        for (i, (&ac, &gc)) in actual.iter().zip(guess).enumerate() {
+            if ac == gc {
+                digits[i] = 2;
+            } else {
+                unused[(ac & 31) as usize] += 1;
+            }
+        }
+
+        for (i, &gc) in guess.iter().enumerate() {
+            if digits[i] == 2 {
+                continue;
+            }
+
+            let slot = &mut unused[(gc & 31) as usize];
+            if 0 < *slot {
+                digits[i] = 1;
+                *slot -= 1;
+            }
+        }
+
+        digits[..guess.len()]
+            .iter()
+            .rev()
+            .fold(0, |p, &d| p * 3 + d as Pattern)
+    }
+
+    /// Packs a comparison no longer than [MAX_LEN].
+    pub fn from_chars(cmp: &[char]) -> Pattern {
+        debug_assert!(cmp.len() <= MAX_LEN, "Comparison too long to pack");
+        cmp.iter().rev().fold(0, |p, ch| {
+            let d = DIGITS.iter().position(|d| d == ch).unwrap_or(0);
+            p * 3 + d as Pattern
+        })
+    }
+
+    pub fn to_chars(mut pattern: Pattern, len: usize) -> Vec<char> {
+        let mut cmp = Vec::with_capacity(len);
+        for _ in 0..len {
+            cmp.push(DIGITS[(pattern % 3) as usize]);
+            pattern /= 3;
+        }
+        cmp
+    }
+
+    /// The pattern of a correct guess.
+    pub fn solved(len: usize) -> Pattern {
+        (0..len).fold(0, |p, _| p * 3 + 2)
+    }
+
+    #[test]
+    fn test_pattern() {
+        use crate::wordl::compare;
+
+        let pairs = [
+            ("SLUMP", "PLUMP"),
+            ("SLUMP", "MAPLE"),
+            ("CACAO", "ANANA"),
+            ("AQUAS", "PUMAS"),
+            ("SLUMP", "SLUMP"),
+            ("ABBEY", "BABES"),
+        ];
+        for (actual, guess) in pairs {
+            let cmp = compare(actual, guess);
+            let p = of(actual.as_bytes(), guess.as_bytes());
+            assert_eq!(p, from_chars(&cmp));
+            assert_eq!(to_chars(p, 5), cmp);
+        }
+        assert_eq!(of(b"SLUMP", b"SLUMP"), solved(5));
+    }
+}
+
+pub mod matrix {
+    use std::collections::HashMap;
+    use std::fs::{self, File};
+    use std::io::{BufWriter, Write};
+    use std::path::{Path, PathBuf};
+
+    use memmap2::Mmap;
+
+    use crate::dicts::DICT;
+    use crate::solve::pattern::{self, Pattern, MAX_LEN};
+
+    const MAGIC: &[u8; 4] = b"WRFM";
+    const VERSION: u16 = 1;
+    const HEADER_LEN: usize = 20;
+
+    /// Overrides where [mkmatrix] writes matrices and [FeedbackMatrix::cached] looks for them.
+    pub const CACHE_ENV: &str = "WORDLE_RS_CACHE_DIR";
+
+    /// The most [FeedbackMatrix::cached] will build in memory. Bigger ones need [mkmatrix].
+    pub const MEMORY_LIMIT: usize = 128 << 20;
+
+    enum Storage {
+        Owned(Vec<Pattern>),
+        Mapped(Mmap),
+    }
+
+    /**
+     * Every guess compared against every answer for one word length, indexed by the
+     * position of each word in [FeedbackMatrix::words]. The same list serves as both
+     * the guesses and the answers.
+     */
+    pub struct FeedbackMatrix {
+        len: usize,
+        words: Vec<String>,
+        index: HashMap<String, usize>,
+        storage: Storage,
+    }
+
+    impl FeedbackMatrix {
+        pub fn build(len: usize) -> Result<Self, String> {
+            Self::from_words(len, DICT.word_lens(len))
+        }
+
+        pub fn from_words(len: usize, words: Vec<String>) -> Result<Self, String> {
+            check_len(len)?;
+            if let Some(w) = words.iter().find(|w| w.len() != len) {
+                return Err(format!("Word {:?} not of length {}!", w, len));
+            }
+
+            let data = compute(&words);
+            Ok(Self::with_storage(len, words, Storage::Owned(data)))
+        }
+
+        /**
+         * Maps the matrix persisted by [mkmatrix] if there is a current one, otherwise builds
+         * it in memory, as long as that stays under [MEMORY_LIMIT].
+         */
+        pub fn cached(len: usize) -> Result<Self, String> {
+            Self::cached_at(cache_path(len).as_deref(), len, MEMORY_LIMIT)
+        }
+
+        /// Like [FeedbackMatrix::cached], looking in `dir` instead of the cache directory.
+        pub fn cached_in(dir: &Path, len: usize) -> Result<Self, String> {
+            Self::cached_at(Some(&file_in(dir, len)), len, MEMORY_LIMIT)
+        }
+
+        fn cached_at(path: Option<&Path>, len: usize, limit: usize) -> Result<Self, String> {
+            if let Some(path) = path {
+                if let Ok(matrix) = Self::load(path, len) {
+                    return Ok(matrix);
+                }
+            }
+
+            check_len(len)?;
+            let size = size_of(DICT.word_lens(len).len());
+            if limit < size {
+                return Err(format!(
+                    "The feedback matrix for length {} takes {} MB. \
+                     Run `wordle-rs --mkmatrix -n {}` to build it on disk once.",
+                    len,
+                    size >> 20,
+                    len
+                ));
+            }
+            Self::build(len)
+        }
+
+        /**
+         * Memory-maps a matrix written by [FeedbackMatrix::save]. Fails if the file was built
+         * from a different word list than the current dictionary.
+         */
+        pub fn load(path: &Path, len: usize) -> Result<Self, String> {
+            check_len(len)?;
+            let words = DICT.word_lens(len);
+
+            let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            // Safety: the cache file is only ever replaced by renaming, never modified in place.
+            let map =
+                unsafe { Mmap::map(&file) }.map_err(|e| format!("{}: {}", path.display(), e))?;
+
+            if map.len() < HEADER_LEN || map[..HEADER_LEN] != header(len, &words)[..] {
+                return Err(format!("{} is stale or corrupt.", path.display()));
+            }
+            if map.len() != HEADER_LEN + size_of(words.len()) {
+                return Err(format!("{} is truncated.", path.display()));
+            }
+
+            Ok(Self::with_storage(len, words, Storage::Mapped(map)))
+        }
+
+        pub fn save(&self, path: &Path) -> Result<(), String> {
+            let err = |e: std::io::Error| format!("{}: {}", path.display(), e);
+
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(err)?;
+            }
+
+            // Write beside the destination and rename so readers never map a partial file.
+            let tmp = path.with_extension("tmp");
+            {
+                let mut out = BufWriter::new(File::create(&tmp).map_err(err)?);
+                out.write_all(&header(self.len, &self.words)).map_err(err)?;
+                let n = self.words.len();
+                for g in 0..n {
+                    for a in 0..n {
+                        out.write_all(&self.get(g, a).to_le_bytes()).map_err(err)?;
+                    }
+                }
+                out.flush().map_err(err)?;
+            }
+            fs::rename(&tmp, path).map_err(err)
+        }
+
+        fn with_storage(len: usize, words: Vec<String>, storage: Storage) -> Self {
+            let index = words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| (w.clone(), i))
+                .collect();
+
+            Self {
+                len,
+                words,
+                index,
+                storage,
+            }
+        }
+
+        pub fn word_len(&self) -> usize {
+            self.len
+        }
+
+        pub fn words(&self) -> &[String] {
+            &self.words
+        }
+
+        pub fn index_of(&self, word: &str) -> Option<usize> {
+            self.index.get(word).copied()
+        }
+
+        /// The pattern shown for `guess` when the answer is `answer`.
+        pub fn get(&self, guess: usize, answer: usize) -> Pattern {
+            let i = guess * self.words.len() + answer;
+            match &self.storage {
+                Storage::Owned(data) => data[i],
+                Storage::Mapped(map) => {
+                    let o = HEADER_LEN + 2 * i;
+                    Pattern::from_le_bytes([map[o], map[o + 1]])
+                }
+            }
+        }
+    }
+
+    /// Bytes taken by the patterns of a matrix over `words` words.
+    fn size_of(words: usize) -> usize {
+        std::mem::size_of::<Pattern>() * words * words
+    }
+
+    fn check_len(len: usize) -> Result<(), String> {
+        if len == 0 || MAX_LEN < len {
+            Err(format!(
+                "Feedback matrices only support words of length 1 to {}.",
+                MAX_LEN
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Fills the matrix row by row, splitting the rows across every available core.
+    fn compute(words: &[String]) -> Vec<Pattern> {
+        let n = words.len();
+        let mut data = vec![0; n * n];
+        if n == 0 {
+            return data;
+        }
+
+        let threads = std::thread::available_parallelism()
+            .map(|t| t.get())
+            .unwrap_or(1);
+        let rows_per_thread = n.div_ceil(threads);
+
+        std::thread::scope(|s| {
+            for (chunk_i, chunk) in data.chunks_mut(rows_per_thread * n).enumerate() {
+                s.spawn(move || {
+                    for (r, row) in chunk.chunks_mut(n).enumerate() {
+                        let guess = words[chunk_i * rows_per_thread + r].as_bytes();
+                        for (cell, answer) in row.iter_mut().zip(words) {
+                            *cell = pattern::of(answer.as_bytes(), guess);
+                        }
+                    }
+                });
+            }
+        });
+
+        data
+    }
+
+    fn header(len: usize, words: &[String]) -> [u8; HEADER_LEN] {
+        // FNV-1a over the word list, so a changed dictionary invalidates the cache.
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for b in words.iter().flat_map(|w| w.bytes().chain(Some(b'\n'))) {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+
+        let mut h = [0; HEADER_LEN];
+        h[0..4].copy_from_slice(MAGIC);
+        h[4..6].copy_from_slice(&VERSION.to_le_bytes());
+        h[6..8].copy_from_slice(&(len as u16).to_le_bytes());
+        h[8..12].copy_from_slice(&(words.len() as u32).to_le_bytes());
+        h[12..20].copy_from_slice(&hash.to_le_bytes());
+        h
+    }
+
+    /// Where the matrix for `len` is cached: under `$WORDLE_RS_CACHE_DIR` if set.
+    pub fn cache_path(len: usize) -> Option<PathBuf> {
+        let dir = match std::env::var_os(CACHE_ENV) {
+            Some(dir) => Some(PathBuf::from(dir)),
+            None => dirs::cache_dir().map(|d| d.join("wordle-rs")),
+        };
+        dir.map(|d| file_in(&d, len))
+    }
+
+    fn file_in(dir: &Path, len: usize) -> PathBuf {
+        dir.join(format!("feedback-{}.bin", len))
+    }
+
+    pub fn mkmatrix(len: usize) -> Result<(), String> {
+        let path = cache_path(len).ok_or("No cache directory available.")?;
+        check_len(len)?;
+        println!(
+            "Building the feedback matrix for length {} ({} MB)...",
+            len,
+            size_of(DICT.word_lens(len).len()) >> 20
+        );
+        FeedbackMatrix::build(len)?.save(&path)?;
+        println!("Wrote {}", path.display());
+        Ok(())
+    }
+
+    #[test]
+    fn test_matrix() {
+        use crate::wordl::compare;
+
+        let words: Vec<String> = ["CACAO", "ANANA", "SLUMP", "PLUMP", "MAPLE"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        let matrix = FeedbackMatrix::from_words(5, words.clone()).unwrap();
+
+        for (g, guess) in words.iter().enumerate() {
+            for (a, answer) in words.iter().enumerate() {
+                let expected = pattern::from_chars(&compare(answer, guess));
+                assert_eq!(matrix.get(g, a), expected);
+            }
+        }
+        assert_eq!(matrix.index_of("PLUMP"), Some(3));
+    }
+
+    #[test]
+    fn test_matrix_persist() {
+        let path = std::env::temp_dir().join(format!("wordle-rs-test-{}.bin", std::process::id()));
+
+        // Nothing cached yet, so it's built, then mapped once saved.
+        let built = FeedbackMatrix::cached_at(Some(&path), 3, MEMORY_LIMIT).unwrap();
+        assert!(matches!(built.storage, Storage::Owned(_)));
+        built.save(&path).unwrap();
+        let loaded = FeedbackMatrix::cached_at(Some(&path), 3, 0).unwrap();
+        assert!(matches!(loaded.storage, Storage::Mapped(_)));
+        let mismatched = FeedbackMatrix::load(&path, 4);
+        fs::remove_file(&path).unwrap();
+
+        // Too big to build without asking.
+        assert!(FeedbackMatrix::cached_at(None, 3, 1024).is_err());
+
+        let n = built.words().len();
+        assert_eq!(loaded.words(), built.words());
+        for (g, a) in [(0, 0), (1, n - 1), (n - 1, 2), (n / 2, n / 3)] {
+            assert_eq!(loaded.get(g, a), built.get(g, a));
+        }
+        assert!(mismatched.is_err());
+    }
+}
+
+pub mod reverse {
+    use std::io::{Read, Write};
+
+    use crate::dicts::DICT;
+    use crate::solve::matrix::FeedbackMatrix;
+    use crate::solve::pattern::{self, Pattern, MAX_LEN};
+    use crate::wordl::{compare, join, normalize, split};
+
+    /// How many words to print for any one list before summarizing the rest.
+    const SHOWN: usize = 10;
+
+    /// The rows of a pasted result grid. Lines that aren't tiles (headers, blanks) are skipped.
+    pub fn parse_grid(text: &str) -> Result<Vec<Vec<char>>, String> {
+        let rows: Vec<Vec<char>> = text.lines().filter_map(split).collect();
+
+        match rows.first() {
+            None => Err("No rows of tiles found.".to_string()),
+            Some(first) => match rows.iter().find(|r| r.len() != first.len()) {
+                Some(r) => Err(format!(
+                    "Row {} is not of length {}!",
+                    join(r, true),
+                    first.len()
+                )),
+                None => Ok(rows),
+            },
+        }
+    }
+
+    /// Every word in the dictionary that shows `cmp` when guessed against `answer`.
+    pub fn guesses_for(answer: &str, cmp: &[char]) -> Vec<String> {
+        let words = DICT.word_lens(cmp.len());
+
+        // Too long to pack, so compare the slow way.
+        if MAX_LEN < cmp.len() {
+            return words
+                .into_iter()
+                .filter(|g| compare(answer, g) == cmp)
+                .collect();
+        }
+
+        let target = pattern::from_chars(cmp);
+        words
+            .into_iter()
+            .filter(|g| pattern::of(answer.as_bytes(), g.as_bytes()) == target)
+            .collect()
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Ranked {
+        pub answer: String,
+        /// Rows that at least one guess could have produced.
+        pub rows: usize,
+        /// Log-probability of a random guess producing each explained row, summed over the rows.
+        pub likelihood: f64,
+    }
+
+    /**
+     * Orders every answer in the matrix by how many rows of the grid it explains, then by how
+     * likely those rows are to come from guesses picked at random.
+     */
+    pub fn rank_answers(matrix: &FeedbackMatrix, rows: &[Pattern]) -> Vec<Ranked> {
+        let n = matrix.words().len();
+
+        let mut ranked: Vec<Ranked> = (0..n)
+            .map(|a| {
+                let mut explained = 0;
+                let mut likelihood = 0.0;
+                for &p in rows {
+                    let fits = (0..n).filter(|&g| matrix.get(g, a) == p).count();
+                    if 0 < fits {
+                        explained += 1;
+                        likelihood += (fits as f64 / n as f64).ln();
+                    }
+                }
+
+                Ranked {
+                    answer: matrix.words()[a].clone(),
+                    rows: explained,
+                    likelihood,
+                }
+            })
+            .collect();
+
+        ranked.sort_by(|x, y| {
+            y.rows
+                .cmp(&x.rows)
+                .then(y.likelihood.total_cmp(&x.likelihood))
+        });
+        ranked
+    }
+
+    fn summarize(words: &[String]) -> String {
+        let mut shown = words
+            .iter()
+            .take(SHOWN)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if SHOWN < words.len() {
+            shown.push_str(&format!(" and {} more", words.len() - SHOWN));
+        }
+        shown
+    }
+
+    /**
+     * Reads a grid from `input`. With an answer, lists the guesses behind each row; without one,
+     * ranks the answers most consistent with the grid.
+     */
+    pub fn reverse(
+        input: &mut dyn Read,
+        output: &mut dyn Write,
+        answer_raw: Option<String>,
+        ascii: bool,
+    ) -> Result<(), String> {
+        let mut text = String::new();
+        input.read_to_string(&mut text).map_err(|e| e.to_string())?;
+        let rows = parse_grid(&text)?;
+        let len = rows[0].len();
+
+        if let Some(answer_raw) = answer_raw {
+            let answer = normalize(&answer_raw);
+            if answer.len() != len {
+                return Err(format!("Answer {:?} not of length {}!", answer, len));
+            }
+
+            for row in &rows {
+                let guesses = guesses_for(&answer, row);
+                writeln!(
+                    output,
+                    "{}  {}",
+                    join(row, ascii),
+                    if guesses.is_empty() {
+                        "No guesses fit.".to_string()
+                    } else {
+                        summarize(&guesses)
+                    }
+                )
+                .unwrap();
+            }
+        } else {
+            let matrix = FeedbackMatrix::cached(len)?;
+            let patterns: Vec<Pattern> = rows.iter().map(|r| pattern::from_chars(r)).collect();
+
+            for ranked in rank_answers(&matrix, &patterns).iter().take(SHOWN) {
+                writeln!(
+                    output,
+                    "{}  {}/{} rows",
+                    ranked.answer,
+                    ranked.rows,
+                    rows.len(),
+                )
+                .unwrap();
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reverse() {
+        use crate::wordl::{MATCH, MISS};
+
+        let rows = parse_grid("wordle-rs 2/6\n\nx@@@@\n🟩🟩🟩🟩🟩\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], vec![MISS, MATCH, MATCH, MATCH, MATCH]);
+        assert!(parse_grid("x@@\n@@@@@").is_err());
+
+        let guesses = guesses_for("SLUMP", &rows[0]);
+        assert!(guesses.contains(&"PLUMP".to_string()));
+        assert!(!guesses.contains(&"SLUMP".to_string()));
+
+        // Longer than a pattern can hold.
+        let long = vec![MATCH; 11];
+        let answer = DICT.word_lens(11).remove(0);
+        assert_eq!(guesses_for(&answer, &long), vec![answer.clone()]);
+        let mut output = Vec::new();
+        let grid = format!("{}\n{}\n", "x".repeat(11), "@".repeat(11));
+        reverse(&mut grid.as_bytes(), &mut output, Some(answer), true).unwrap();
+        assert!(reverse(&mut grid.as_bytes(), &mut output, None, true).is_err());
+
+        let words: Vec<String> = ["CLUMP", "PLUMP", "SLUMP", "TIGHT"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        let matrix = FeedbackMatrix::from_words(5, words).unwrap();
+        let ranked = rank_answers(&matrix, &[pattern::from_chars(&rows[0])]);
+        for r in &ranked[..3] {
+            assert_eq!(r.rows, 1);
+            assert_eq!(r.likelihood, (2.0f64 / 4.0).ln());
+        }
+        assert_eq!(ranked[3].answer, "TIGHT");
+        assert_eq!(ranked[3].rows, 0);
+    }
+}
+
+pub mod strategy {
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use rand::seq::SliceRandom;
+
+    use crate::dicts::DICT;
+    use crate::engine::host::Score;
+    use crate::solve::matrix::FeedbackMatrix;
+    use crate::solve::pattern::{self, MAX_LEN};
+    use crate::wordl::{join, Game, Rules};
+
+    /// What a strategy can see of the game in progress.
+    pub struct State<'a> {
+        pub len: usize,
+        pub rules: Rules,
+        /// Every accepted guess with its comparison, in order.
+        pub history: &'a [(String, Vec<char>)],
+    }
+
+    /**
+     * A solver. The same value plays game after game: [Strategy::start] is called before each
+     * one, then [Strategy::next_guess] and [Strategy::observe] alternate until it's over.
+     */
+    pub trait Strategy {
+        fn name(&self) -> &str;
+
+        /// Fails if the strategy can't play words of this length.
+        fn start(&mut self, _len: usize, _rules: Rules) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn next_guess(&mut self, state: &State) -> String;
+
+        fn observe(&mut self, guess: &str, pattern: &[char]);
+    }
+
+    pub const NAMES: [&str; 4] = ["random", "frequency", "entropy", "minimax"];
+
+    pub fn by_name(name: &str) -> Option<Box<dyn Strategy>> {
+        match name {
+            "random" => Some(Box::new(RandomConsistent::default())),
+            "frequency" => Some(Box::new(Frequency::default())),
+            "entropy" => Some(Box::new(Entropy::default())),
+            "minimax" => Some(Box::new(Minimax::default())),
+            _ => None,
+        }
+    }
+
+    /// Dictionary words that agree with every comparison observed so far.
+    #[derive(Default)]
+    pub struct Candidates {
+        words: Vec<String>,
+    }
+
+    impl Candidates {
+        pub fn start(&mut self, len: usize) {
+            self.words = DICT.word_lens(len);
+        }
+
+        pub fn observe(&mut self, guess: &str, pattern: &[char]) {
+            let target = pattern::from_chars(pattern);
+            self.words
+                .retain(|w| pattern::of(w.as_bytes(), guess.as_bytes()) == target);
+        }
+
+        pub fn words(&self) -> &[String] {
+            &self.words
+        }
+    }
+
+    /// Guesses any word that could still be the answer.
+    #[derive(Default)]
+    pub struct RandomConsistent {
+        candidates: Candidates,
+    }
+
+    impl Strategy for RandomConsistent {
+        fn name(&self) -> &str {
+            "random"
+        }
+
+        fn start(&mut self, len: usize, _rules: Rules) -> Result<(), String> {
+            self.candidates.start(len);
+            Ok(())
+        }
+
+        fn next_guess(&mut self, _state: &State) -> String {
+            let mut rng = rand::thread_rng();
+            self.candidates
+                .words()
+                .choose(&mut rng)
+                .cloned()
+                .unwrap_or_default()
+        }
+
+        fn observe(&mut self, guess: &str, pattern: &[char]) {
+            self.candidates.observe(guess, pattern);
+        }
+    }
+
+    /// Guesses the possible answer whose distinct letters are most common among the others.
+    #[derive(Default)]
+    pub struct Frequency {
+        candidates: Candidates,
+    }
+
+    impl Strategy for Frequency {
+        fn name(&self) -> &str {
+            "frequency"
+        }
+
+        fn start(&mut self, len: usize, _rules: Rules) -> Result<(), String> {
+            self.candidates.start(len);
+            Ok(())
+        }
+
+        fn next_guess(&mut self, _state: &State) -> String {
+            let words = self.candidates.words();
+
+            let mut counts = [0usize; 32];
+            for w in words {
+                for b in distinct(w) {
+                    counts[(b & 31) as usize] += 1;
+                }
+            }
+
+            words
+                .iter()
+                .max_by_key(|w| {
+                    distinct(w)
+                        .map(|b| counts[(b & 31) as usize])
+                        .sum::<usize>()
+                })
+                .cloned()
+                .unwrap_or_default()
+        }
+
+        fn observe(&mut self, guess: &str, pattern: &[char]) {
+            self.candidates.observe(guess, pattern);
+        }
+    }
+
+    fn distinct(word: &str) -> impl Iterator<Item = u8> + '_ {
+        let bytes = word.as_bytes();
+        bytes
+            .iter()
+            .enumerate()
+            .filter(move |&(i, b)| !bytes[..i].contains(b))
+            .map(|(_, &b)| b)
+    }
+
+    /**
+     * The matrix and candidates shared by the strategies that look ahead. The matrix is only
+     * rebuilt when the word length changes.
+     */
+    #[derive(Default)]
+    struct Lookahead {
+        matrix: Option<Arc<FeedbackMatrix>>,
+        candidates: Candidates,
+        hard: bool,
+        /// Where to look for matrices instead of the cache directory.
+        cache_dir: Option<PathBuf>,
+    }
+
+    impl Lookahead {
+        fn in_dir(dir: PathBuf) -> Self {
+            Self {
+                cache_dir: Some(dir),
+                ..Self::default()
+            }
+        }
+
+        fn start(&mut self, len: usize, rules: Rules) -> Result<(), String> {
+            if self.matrix.as_ref().map(|m| m.word_len()) != Some(len) {
+                let matrix = match &self.cache_dir {
+                    Some(dir) => FeedbackMatrix::cached_in(dir, len)?,
+                    None => FeedbackMatrix::cached(len)?,
+                };
+                self.matrix = Some(Arc::new(matrix));
+            }
+            self.candidates.start(len);
+            self.hard = rules.hard;
+            Ok(())
+        }
+
+        /**
+         * Picks the guess with the lowest cost, where cost is computed from how the remaining
+         * candidates would be split up by each pattern. Possible answers win ties.
+         */
+        fn best_by<F>(&self, cost: F) -> String
+        where
+            F: Fn(&[usize]) -> f64,
+        {
+            let words = self.candidates.words();
+            if words.len() <= 2 {
+                return words.first().cloned().unwrap_or_default();
+            }
+
+            let matrix = self.matrix.as_ref().expect("Strategy not started");
+            // Candidates keep the dictionary's order, so these indices are sorted.
+            let answers: Vec<usize> = words.iter().filter_map(|w| matrix.index_of(w)).collect();
+            // Hard mode only allows guesses that could be the answer.
+            let pool: Vec<usize> = if self.hard {
+                answers.clone()
+            } else {
+                (0..matrix.words().len()).collect()
+            };
+
+            let mut buckets = vec![0usize; pattern::solved(matrix.word_len()) as usize + 1];
+            let mut best = (f64::INFINITY, true, 0);
+            for g in pool {
+                buckets.iter_mut().for_each(|b| *b = 0);
+                for &a in &answers {
+                    buckets[matrix.get(g, a) as usize] += 1;
+                }
+
+                let c = cost(&buckets);
+                let outside = answers.binary_search(&g).is_err();
+                if c < best.0 || (c == best.0 && !outside && best.1) {
+                    best = (c, outside, g);
+                }
+            }
+
+            matrix.words()[best.2].clone()
+        }
+    }
+
+    /// Guesses whatever is expected to reveal the most information about the answer.
+    #[derive(Default)]
+    pub struct Entropy {
+        lookahead: Lookahead,
+    }
+
+    impl Entropy {
+        /// Keeps its feedback matrices in `dir` rather than the cache directory.
+        pub fn with_cache_dir(dir: PathBuf) -> Self {
+            Self {
+                lookahead: Lookahead::in_dir(dir),
+            }
+        }
+    }
+
+    impl Strategy for Entropy {
+        fn name(&self) -> &str {
+            "entropy"
+        }
+
+        fn start(&mut self, len: usize, rules: Rules) -> Result<(), String> {
+            self.lookahead.start(len, rules)
+        }
+
+        fn next_guess(&mut self, _state: &State) -> String {
+            self.lookahead.best_by(|buckets| {
+                let total: usize = buckets.iter().sum();
+                buckets
+                    .iter()
+                    .filter(|&&b| 0 < b)
+                    .map(|&b| {
+                        let p = b as f64 / total as f64;
+                        p * p.ln()
+                    })
+                    .sum()
+            })
+        }
+
+        fn observe(&mut self, guess: &str, pattern: &[char]) {
+            self.lookahead.candidates.observe(guess, pattern);
+        }
+    }
+
+    /// Guesses whatever leaves the fewest possible answers in the worst case.
+    #[derive(Default)]
+    pub struct Minimax {
+        lookahead: Lookahead,
+    }
+
+    impl Minimax {
+        /// Keeps its feedback matrices in `dir` rather than the cache directory.
+        pub fn with_cache_dir(dir: PathBuf) -> Self {
+            Self {
+                lookahead: Lookahead::in_dir(dir),
+            }
+        }
+    }
+
+    impl Strategy for Minimax {
+        fn name(&self) -> &str {
+            "minimax"
+        }
+
+        fn start(&mut self, len: usize, rules: Rules) -> Result<(), String> {
+            self.lookahead.start(len, rules)
+        }
+
+        fn next_guess(&mut self, _state: &State) -> String {
+            self.lookahead
+                .best_by(|buckets| buckets.iter().copied().max().unwrap_or(0) as f64)
+        }
+
+        fn observe(&mut self, guess: &str, pattern: &[char]) {
+            self.lookahead.candidates.observe(guess, pattern);
+        }
+    }
+
+    /**
+     * Lets the strategy play a whole game of `answer`. A guess the game rejects ends the game
+     * as a loss, so a misbehaving strategy can't loop forever. Fails if the answer is too long
+     * for strategies or this one can't start.
+     */
+    pub fn autoplay(
+        strategy: &mut dyn Strategy,
+        answer: &str,
+        rules: Rules,
+    ) -> Result<Game, String> {
+        let mut game = Game::with_rules(answer, rules);
+        check_len(game.len())?;
+        strategy.start(game.len(), rules)?;
+
+        while 0 < game.guesses_remaining() {
+            let guess = strategy.next_guess(&State {
+                len: game.len(),
+                rules,
+                history: game.history(),
+            });
+            match game.guess(&guess) {
+                Ok(cmp) => strategy.observe(&guess, &cmp),
+                Err(_) => break,
+            }
+        }
+
+        Ok(game)
+    }
+
+    /// Autoplays every answer, tallying the results.
+    pub fn benchmark(
+        strategy: &mut dyn Strategy,
+        answers: &[String],
+        rules: Rules,
+        mut each: impl FnMut(&Game),
+    ) -> Result<Score, String> {
+        let mut score = Score::default();
+        for answer in answers {
+            let game = autoplay(strategy, answer, rules)?;
+            score.played += 1;
+            if game.is_won() {
+                score.won += 1;
+                score.guesses += game.guesses_made();
+            }
+            each(&game);
+        }
+        Ok(score)
+    }
+
+    /// Autoplays `answer`, printing each guess the way [crate::wordl::play] does.
+    pub fn watch(
+        output: &mut dyn Write,
+        strategy: &mut dyn Strategy,
+        answer: &str,
+        rules: Rules,
+        ascii: bool,
+    ) -> Result<(), String> {
+        let game = autoplay(strategy, answer, rules)?;
+
+        for (guess, cmp) in game.history() {
+            for c in guess.chars() {
+                write!(output, "{} ", c).unwrap();
+            }
+            writeln!(output).unwrap();
+            writeln!(output, "{}", join(cmp, ascii)).unwrap();
+        }
+        writeln!(output, "{}", game.summary()).unwrap();
+
+        Ok(())
+    }
+
+    pub fn check_len(len: usize) -> Result<(), String> {
+        if (1..=MAX_LEN).contains(&len) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Strategies only support words of length 1 to {}.",
+                MAX_LEN
+            ))
+        }
+    }
+
+    #[test]
+    fn test_strategies() {
+        // Look-ahead strategies build their own matrices rather than use whatever's cached.
+        let empty = std::env::temp_dir().join(format!("wordle-rs-cache-{}", std::process::id()));
+        let strategies: Vec<Box<dyn Strategy>> = vec![
+            by_name("random").unwrap(),
+            by_name("frequency").unwrap(),
+            Box::new(Entropy::with_cache_dir(empty.clone())),
+            Box::new(Minimax::with_cache_dir(empty)),
+        ];
+        assert_eq!(
+            strategies.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            NAMES
+        );
+        for mut strategy in strategies {
+            let name = strategy.name().to_string();
+            for answer in ["LAMP", "TIDY"] {
+                let game = autoplay(strategy.as_mut(), answer, Rules::default()).unwrap();
+                println!("{}: {} in {}", name, answer, game.guesses_made());
+                assert!(0 < game.guesses_made());
+                if name != "random" {
+                    assert!(game.is_won());
+                }
+            }
+            assert!(autoplay(strategy.as_mut(), "ABCDEFGHIJK", Rules::default()).is_err());
+        }
+    }
+}
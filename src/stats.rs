@@ -0,0 +1,1583 @@
+pub mod store {
+    use std::fs::{self, File, OpenOptions};
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+
+    use serde::{Deserialize, Serialize};
+    use serde_json::{json, Value};
+
+    use crate::wordl::Game;
+
+    /// The current version of the stats file's schema. Older files are migrated on load.
+    pub const VERSION: u64 = 1;
+
+    /// One finished game.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct Record {
+        /// Local date the game finished, as YYYY-MM-DD.
+        pub date: String,
+        /// Unix timestamp the game finished.
+        pub time: i64,
+        pub secret: String,
+        pub len: usize,
+        pub guesses: Vec<String>,
+        pub max_guesses: usize,
+        pub won: bool,
+        #[serde(default)]
+        pub hard: bool,
+        #[serde(default)]
+        pub hints: usize,
+        /// The daily puzzle number, if it was one.
+        #[serde(default)]
+        pub puzzle: Option<u32>,
+        /// Milliseconds on the clock when the game ended.
+        #[serde(default)]
+        pub elapsed_ms: Option<u64>,
+        /// Milliseconds on the clock at each guess.
+        #[serde(default)]
+        pub guess_ms: Vec<u64>,
+        /// The time limit in seconds, if there was one.
+        #[serde(default)]
+        pub time_limit: Option<u64>,
+    }
+
+    impl Record {
+        pub fn from_game(game: &Game) -> Self {
+            let now = chrono::Local::now();
+
+            Self {
+                date: now.format("%Y-%m-%d").to_string(),
+                time: now.timestamp(),
+                secret: game.actual().to_string(),
+                len: game.len(),
+                guesses: game.history().iter().map(|(g, _)| g.clone()).collect(),
+                max_guesses: game.rules().guesses,
+                won: game.is_won(),
+                hard: game.rules().hard,
+                hints: game.hints_used(),
+                puzzle: game.puzzle(),
+                elapsed_ms: Some(game.elapsed().as_millis() as u64),
+                guess_ms: game.times().iter().map(|t| t.as_millis() as u64).collect(),
+                time_limit: game.rules().time_limit.map(|l| l.as_secs()),
+            }
+        }
+    }
+
+    /**
+     * The stats file, guarded by a lock file beside it so that several terminals can record
+     * games at once. Writes replace the file by renaming, so readers never see half of one.
+     */
+    #[derive(Debug, Clone)]
+    pub struct Store {
+        path: PathBuf,
+    }
+
+    impl Store {
+        pub fn at(path: &Path) -> Self {
+            Self {
+                path: path.to_path_buf(),
+            }
+        }
+
+        /// The store under the XDG data directory, e.g. `~/.local/share/wordle-rs/stats.json`.
+        pub fn default_path() -> Option<PathBuf> {
+            dirs::data_dir().map(|d| d.join("wordle-rs").join("stats.json"))
+        }
+
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        pub fn load(&self) -> Result<Vec<Record>, String> {
+            let lock = self.lock()?;
+            lock.lock_shared().map_err(|e| self.err(e))?;
+            self.read()
+        }
+
+        pub fn record(&self, record: Record) -> Result<(), String> {
+            self.update(|records| records.push(record))
+        }
+
+        /// Reads, modifies and rewrites the records while holding the lock exclusively.
+        pub fn update<F>(&self, f: F) -> Result<(), String>
+        where
+            F: FnOnce(&mut Vec<Record>),
+        {
+            let lock = self.lock()?;
+            lock.lock().map_err(|e| self.err(e))?;
+
+            let mut records = self.read()?;
+            f(&mut records);
+            self.write(&records)
+        }
+
+        fn lock(&self) -> Result<File, String> {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir).map_err(|e| self.err(e))?;
+            }
+
+            OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(self.path.with_extension("lock"))
+                .map_err(|e| self.err(e))
+        }
+
+        fn read(&self) -> Result<Vec<Record>, String> {
+            let text = match fs::read_to_string(&self.path) {
+                Ok(text) => text,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(self.err(e)),
+            };
+
+            let doc: Value = serde_json::from_str(&text).map_err(|e| self.err(e))?;
+            migrate(doc).map_err(|e| format!("{}: {}", self.path.display(), e))
+        }
+
+        fn write(&self, records: &[Record]) -> Result<(), String> {
+            let doc = json!({ "version": VERSION, "games": records });
+            let tmp = self.path.with_extension("tmp");
+            {
+                let mut out = File::create(&tmp).map_err(|e| self.err(e))?;
+                serde_json::to_writer_pretty(&mut out, &doc).map_err(|e| self.err(e))?;
+                out.flush().map_err(|e| self.err(e))?;
+            }
+            fs::rename(&tmp, &self.path).map_err(|e| self.err(e))
+        }
+
+        fn err(&self, e: impl std::fmt::Display) -> String {
+            format!("{}: {}", self.path.display(), e)
+        }
+    }
+
+    /// Upgrades a stats document of any known version to the current [VERSION].
+    pub(crate) fn migrate(mut doc: Value) -> Result<Vec<Record>, String> {
+        let mut version = doc.get("version").and_then(Value::as_u64).unwrap_or(0);
+        if VERSION < version {
+            return Err(format!(
+                "Stats are from a newer version of wordle-rs (schema {}).",
+                version
+            ));
+        }
+
+        while version < VERSION {
+            doc = upgrade(version, doc)?;
+            version += 1;
+        }
+
+        serde_json::from_value(doc["games"].take()).map_err(|e| e.to_string())
+    }
+
+    /**
+     * Upgrades a stats document from `version` to the next one. Version 0 is unversioned: a
+     * bare list of games, or an object holding them under `games`.
+     */
+    fn upgrade(version: u64, doc: Value) -> Result<Value, String> {
+        match version {
+            0 => {
+                let games = match doc {
+                    Value::Array(_) => doc,
+                    Value::Object(mut map) => {
+                        map.remove("games").ok_or("Stats file has no games.")?
+                    }
+                    _ => return Err("Stats file is not a list of games.".to_string()),
+                };
+                Ok(json!({ "version": 1, "games": games }))
+            }
+            _ => Err(format!("Unknown stats schema {}.", version)),
+        }
+    }
+
+    #[test]
+    fn test_store() {
+        let dir = std::env::temp_dir().join(format!("wordle-rs-store-{}", std::process::id()));
+        let store = Store::at(&dir.join("stats.json"));
+
+        let mut game = Game::new("slump");
+        game.guess("plump").unwrap();
+        game.hint().unwrap();
+        game.guess("slump").unwrap();
+        store.record(Record::from_game(&game)).unwrap();
+
+        let records = store.load().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].guesses, vec!["PLUMP", "SLUMP"]);
+        assert!(records[0].won);
+        assert_eq!(records[0].hints, 1);
+
+        // Concurrent writers must not lose each other's records.
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let store = store.clone();
+                let record = records[0].clone();
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        store.record(record.clone()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for t in threads {
+            t.join().unwrap();
+        }
+        assert_eq!(store.load().unwrap().len(), 41);
+
+        fs::write(store.path(), r#"{"version": 999, "games": []}"#).unwrap();
+        assert!(store.load().is_err());
+
+        // Unversioned files from before the schema had a number are still read.
+        let v0 = serde_json::to_string(&records).unwrap();
+        fs::write(store.path(), &v0).unwrap();
+        assert_eq!(store.load().unwrap(), records);
+        fs::write(store.path(), format!(r#"{{"games": {}}}"#, v0)).unwrap();
+        assert_eq!(store.load().unwrap(), records);
+        fs::write(store.path(), "42").unwrap();
+        assert!(store.load().is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+pub mod summary {
+    use std::fmt::Write;
+    use std::str::FromStr;
+
+    use crate::stats::store::{Record, Store};
+
+    /// Widest a distribution bar gets, in characters.
+    const BAR_WIDTH: usize = 30;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum Mode {
+        Normal,
+        Hard,
+        Hinted,
+        Unhinted,
+    }
+
+    impl Mode {
+        pub const NAMES: [&'static str; 4] = ["normal", "hard", "hinted", "unhinted"];
+
+        pub fn matches(&self, record: &Record) -> bool {
+            match self {
+                Mode::Normal => !record.hard,
+                Mode::Hard => record.hard,
+                Mode::Hinted => 0 < record.hints,
+                Mode::Unhinted => record.hints == 0,
+            }
+        }
+    }
+
+    impl FromStr for Mode {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "normal" => Ok(Mode::Normal),
+                "hard" => Ok(Mode::Hard),
+                "hinted" => Ok(Mode::Hinted),
+                "unhinted" => Ok(Mode::Unhinted),
+                _ => Err(format!("Unknown mode {:?}", s)),
+            }
+        }
+    }
+
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+    pub struct Filter {
+        pub len: Option<usize>,
+        pub mode: Option<Mode>,
+    }
+
+    impl Filter {
+        pub fn matches(&self, record: &Record) -> bool {
+            self.len.is_none_or(|len| record.len == len)
+                && self.mode.is_none_or(|mode| mode.matches(record))
+        }
+    }
+
+    #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    pub struct Summary {
+        pub played: usize,
+        pub won: usize,
+        pub streak: usize,
+        pub max_streak: usize,
+        /// How many games were won in each number of guesses, starting from 1.
+        pub distribution: Vec<usize>,
+        /// The most guesses any of the games allowed.
+        pub max_guesses: usize,
+    }
+
+    impl Summary {
+        pub fn of<'a, I>(records: I) -> Self
+        where
+            I: IntoIterator<Item = &'a Record>,
+        {
+            let mut records: Vec<&Record> = records.into_iter().collect();
+            records.sort_by_key(|r| r.time);
+
+            let mut summary = Summary::default();
+            for r in records {
+                summary.played += 1;
+                summary.max_guesses = summary.max_guesses.max(r.max_guesses);
+                if r.won {
+                    summary.won += 1;
+                    summary.streak += 1;
+                    summary.max_streak = summary.max_streak.max(summary.streak);
+
+                    // A win takes at least one guess, though a hand-edited file might disagree.
+                    let n = r.guesses.len().max(1);
+                    if summary.distribution.len() < n {
+                        summary.distribution.resize(n, 0);
+                    }
+                    summary.distribution[n - 1] += 1;
+                } else {
+                    summary.streak = 0;
+                }
+            }
+
+            summary
+        }
+
+        pub fn win_rate(&self) -> f64 {
+            if self.played == 0 {
+                0.0
+            } else {
+                100.0 * self.won as f64 / self.played as f64
+            }
+        }
+
+        pub fn totals(&self) -> String {
+            format!(
+                "Played {}  Won {:.0}%  Streak {}  Max streak {}",
+                self.played,
+                self.win_rate(),
+                self.streak,
+                self.max_streak
+            )
+        }
+
+        /// The totals line followed by one histogram bar per number of guesses the games allowed.
+        pub fn render(&self, ascii: bool) -> String {
+            let mut out = String::new();
+            writeln!(out, "{}", self.totals()).unwrap();
+
+            let most = self.distribution.iter().copied().max().unwrap_or(0).max(1);
+            let rows = self.distribution.len().max(self.max_guesses);
+            let label_width = rows.to_string().len();
+            for i in 0..rows {
+                let count = self.distribution.get(i).copied().unwrap_or(0);
+                let bar = if count == 0 {
+                    0
+                } else {
+                    (count * BAR_WIDTH / most).max(1)
+                };
+                writeln!(
+                    out,
+                    "{:>w$} {} {}",
+                    i + 1,
+                    (if ascii { "#" } else { "█" }).repeat(bar),
+                    count,
+                    w = label_width
+                )
+                .unwrap();
+            }
+
+            out
+        }
+    }
+
+    /// Summarizes the games in the store that pass the filter.
+    pub fn report(store: &Store, filter: Filter, ascii: bool) -> Result<String, String> {
+        let records = store.load()?;
+        let summary = Summary::of(records.iter().filter(|r| filter.matches(r)));
+        Ok(summary.render(ascii))
+    }
+
+    #[test]
+    fn test_summary() {
+        let record = |time: i64, guesses: usize, won: bool, hard: bool| Record {
+            date: "2022-01-20".to_string(),
+            time,
+            secret: "SLUMP".to_string(),
+            len: 5,
+            guesses: vec!["PLUMP".to_string(); guesses],
+            max_guesses: 6,
+            won,
+            hard,
+            hints: 0,
+            puzzle: None,
+            elapsed_ms: None,
+            guess_ms: Vec::new(),
+            time_limit: None,
+        };
+        let records = vec![
+            record(1, 3, true, false),
+            record(2, 6, false, true),
+            record(3, 4, true, true),
+            record(5, 3, true, false),
+            record(4, 2, true, false),
+        ];
+
+        let all = Summary::of(&records);
+        assert_eq!(
+            (all.played, all.won, all.streak, all.max_streak),
+            (5, 4, 3, 3)
+        );
+        assert_eq!(all.distribution, vec![0, 1, 2, 1]);
+        assert_eq!(all.win_rate(), 80.0);
+
+        let hard = Filter {
+            len: Some(5),
+            mode: Some(Mode::Hard),
+        };
+        let summary = Summary::of(records.iter().filter(|r| hard.matches(r)));
+        assert_eq!((summary.played, summary.won, summary.streak), (2, 1, 1));
+
+        let rendered = all.render(true);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[3], format!("3 {} 2", "#".repeat(30)));
+        assert_eq!(lines[6], "6  0");
+
+        // Games with more guesses get a bar for each.
+        let mut long = record(6, 7, true, false);
+        long.max_guesses = 8;
+        let rendered = Summary::of(records.iter().chain([&long])).render(true);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[7], format!("7 {} 1", "#".repeat(15)));
+        assert_eq!(lines[8], "8  0");
+    }
+}
+
+pub mod breakdown {
+    use std::collections::{BTreeMap, HashMap};
+    use std::fmt::{self, Write};
+    use std::str::FromStr;
+
+    use crate::stats::store::{Record, Store};
+    use crate::stats::summary::{Filter, Summary};
+    use crate::wordl::{compare, MISS};
+
+    /// How many of each list to show.
+    const HARDEST: usize = 3;
+    const MISSED: usize = 5;
+    const OPENERS: usize = 3;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum By {
+        /// Word length and mode.
+        Length,
+        Mode,
+    }
+
+    impl By {
+        pub const NAMES: [&'static str; 2] = ["length", "mode"];
+    }
+
+    impl FromStr for By {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "length" => Ok(By::Length),
+                "mode" => Ok(By::Mode),
+                _ => Err(format!("Unknown grouping {:?}", s)),
+            }
+        }
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Group {
+        pub len: Option<usize>,
+        pub hard: bool,
+    }
+
+    impl Group {
+        pub fn of(by: By, record: &Record) -> Self {
+            Self {
+                len: match by {
+                    By::Length => Some(record.len),
+                    By::Mode => None,
+                },
+                hard: record.hard,
+            }
+        }
+    }
+
+    impl fmt::Display for Group {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let mode = if self.hard { "hard" } else { "normal" };
+            match self.len {
+                Some(len) => write!(f, "{} letters, {} mode", len, mode),
+                None => write!(f, "{}{} mode", mode[..1].to_uppercase(), &mode[1..]),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Opener {
+        pub word: String,
+        pub used: usize,
+        pub won: usize,
+        /// Guesses per won game.
+        pub average: f64,
+    }
+
+    /// Aggregates over a set of games, beyond the [Summary] totals.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Detail {
+        pub summary: Summary,
+        /// Guesses per won game.
+        pub average: f64,
+        /// The answers that took the most guesses, losses first, with the guesses if won.
+        pub hardest: Vec<(String, Option<usize>)>,
+        /// Letters of the answer not turned up before the winning guess (or at all), by count.
+        pub missed: Vec<(char, usize)>,
+        /// The most used first guesses.
+        pub openers: Vec<Opener>,
+    }
+
+    fn per_win(guesses: usize, won: usize) -> f64 {
+        if won == 0 {
+            0.0
+        } else {
+            guesses as f64 / won as f64
+        }
+    }
+
+    impl Detail {
+        pub fn of(records: &[&Record]) -> Self {
+            let won: Vec<&&Record> = records.iter().filter(|r| r.won).collect();
+            let average = per_win(won.iter().map(|r| r.guesses.len()).sum(), won.len());
+
+            let mut games: Vec<&&Record> = records.iter().collect();
+            games.sort_by_key(|r| (r.won, std::cmp::Reverse(r.guesses.len()), r.time));
+            let mut hardest: Vec<(String, Option<usize>)> = Vec::new();
+            for r in games {
+                if hardest.len() == HARDEST {
+                    break;
+                }
+                if hardest.iter().all(|(w, _)| w != &r.secret) {
+                    hardest.push((r.secret.clone(), r.won.then_some(r.guesses.len())));
+                }
+            }
+
+            let mut missed: HashMap<char, usize> = HashMap::new();
+            for r in records {
+                let before_last = if r.won {
+                    r.guesses.len().saturating_sub(1)
+                } else {
+                    r.guesses.len()
+                };
+                let mut found: Vec<char> = Vec::new();
+                for g in &r.guesses[..before_last] {
+                    for (ch, c) in g.chars().zip(compare(&r.secret, g)) {
+                        if c != MISS {
+                            found.push(ch);
+                        }
+                    }
+                }
+                for ch in r.secret.chars() {
+                    match found.iter().position(|&f| f == ch) {
+                        Some(i) => {
+                            found.remove(i);
+                        }
+                        None => *missed.entry(ch).or_default() += 1,
+                    }
+                }
+            }
+            let mut missed: Vec<(char, usize)> = missed.into_iter().collect();
+            missed.sort_by_key(|&(ch, n)| (std::cmp::Reverse(n), ch));
+            missed.truncate(MISSED);
+
+            let mut openers: BTreeMap<&str, (usize, usize, usize)> = BTreeMap::new();
+            for r in records {
+                if let Some(first) = r.guesses.first() {
+                    let o = openers.entry(first).or_default();
+                    o.0 += 1;
+                    if r.won {
+                        o.1 += 1;
+                        o.2 += r.guesses.len();
+                    }
+                }
+            }
+            let mut openers: Vec<Opener> = openers
+                .into_iter()
+                .map(|(word, (used, won, guesses))| Opener {
+                    word: word.to_string(),
+                    used,
+                    won,
+                    average: per_win(guesses, won),
+                })
+                .collect();
+            openers.sort_by_key(|o| std::cmp::Reverse(o.used));
+            openers.truncate(OPENERS);
+
+            Self {
+                summary: Summary::of(records.iter().copied()),
+                average,
+                hardest,
+                missed,
+                openers,
+            }
+        }
+
+        /// A few lines, without the totals or the distribution.
+        pub fn render(&self) -> String {
+            let mut out = String::new();
+            writeln!(out, "Average {:.1} guesses per win", self.average).unwrap();
+            if !self.hardest.is_empty() {
+                let hardest: Vec<String> = self
+                    .hardest
+                    .iter()
+                    .map(|(w, n)| match n {
+                        Some(n) => format!("{} ({})", w, n),
+                        None => format!("{} (X)", w),
+                    })
+                    .collect();
+                writeln!(out, "Hardest: {}", hardest.join(", ")).unwrap();
+            }
+            if !self.missed.is_empty() {
+                let missed: Vec<String> = self
+                    .missed
+                    .iter()
+                    .map(|(ch, n)| format!("{} {}", ch, n))
+                    .collect();
+                writeln!(out, "Most missed letters: {}", missed.join(", ")).unwrap();
+            }
+            if !self.openers.is_empty() {
+                let openers: Vec<String> = self
+                    .openers
+                    .iter()
+                    .map(|o| {
+                        format!(
+                            "{} x{} (won {}, avg {:.1})",
+                            o.word, o.used, o.won, o.average
+                        )
+                    })
+                    .collect();
+                writeln!(out, "Openers: {}", openers.join(", ")).unwrap();
+            }
+            out
+        }
+    }
+
+    /// One section per group of the games that pass the filter.
+    pub fn report(store: &Store, filter: Filter, by: By) -> Result<String, String> {
+        let records = store.load()?;
+        let mut groups: BTreeMap<Group, Vec<&Record>> = BTreeMap::new();
+        for r in records.iter().filter(|r| filter.matches(r)) {
+            groups.entry(Group::of(by, r)).or_default().push(r);
+        }
+
+        let mut out = String::new();
+        for (group, records) in groups {
+            let detail = Detail::of(&records);
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            writeln!(out, "{}", group).unwrap();
+            writeln!(out, "{}", detail.summary.totals()).unwrap();
+            out.push_str(&detail.render());
+        }
+        if out.is_empty() {
+            out.push_str("No games yet.\n");
+        }
+        Ok(out)
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let record = |secret: &str, guesses: &[&str], won: bool| Record {
+            date: "2022-01-20".to_string(),
+            time: 0,
+            secret: secret.to_string(),
+            len: secret.len(),
+            guesses: guesses.iter().map(|g| g.to_string()).collect(),
+            max_guesses: 6,
+            won,
+            hard: false,
+            hints: 0,
+            puzzle: None,
+            elapsed_ms: None,
+            guess_ms: Vec::new(),
+            time_limit: None,
+        };
+        let records = [
+            record("SLUMP", &["MAPLE", "PLUMP", "SLUMP"], true),
+            record("TIGHT", &["MAPLE", "TIGHT"], true),
+            record("LUMPY", &["PLUCK", "PLUMB"], false),
+            record("CAT", &["CAT"], true),
+        ];
+        let refs: Vec<&Record> = records.iter().collect();
+
+        let groups: Vec<Group> = refs.iter().map(|r| Group::of(By::Length, r)).collect();
+        assert_eq!(groups[0].to_string(), "5 letters, normal mode");
+        assert_eq!(groups[3].len, Some(3));
+        assert_eq!(Group::of(By::Mode, refs[0]).to_string(), "Normal mode");
+
+        let detail = Detail::of(&refs[..3]);
+        assert_eq!(detail.average, 2.5);
+        assert_eq!(
+            detail.hardest,
+            vec![
+                ("LUMPY".to_string(), None),
+                ("SLUMP".to_string(), Some(3)),
+                ("TIGHT".to_string(), Some(2))
+            ]
+        );
+        // SLUMP's S, TIGHT's T, I, G, H, T and LUMPY's Y were never turned up.
+        assert_eq!(detail.missed[0], ('T', 2));
+        assert_eq!(detail.missed.len(), MISSED);
+        assert_eq!(
+            detail.openers[0],
+            Opener {
+                word: "MAPLE".to_string(),
+                used: 2,
+                won: 2,
+                average: 2.5
+            }
+        );
+        assert!(detail
+            .render()
+            .contains("Openers: MAPLE x2 (won 2, avg 2.5), PLUCK x1"));
+    }
+}
+
+pub mod exchange {
+    use std::collections::HashSet;
+    use std::io::{Read, Write};
+    use std::str::FromStr;
+
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    use crate::stats::store::{Record, Store};
+    use crate::wordl::{compare, join};
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum Format {
+        Csv,
+        Json,
+    }
+
+    impl Format {
+        pub const NAMES: [&'static str; 2] = ["csv", "json"];
+    }
+
+    impl FromStr for Format {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "csv" => Ok(Format::Csv),
+                "json" => Ok(Format::Json),
+                _ => Err(format!("Unknown format {:?}", s)),
+            }
+        }
+    }
+
+    /**
+     * A record as exported: flat enough for a spreadsheet, with each guess's pattern in ASCII
+     * (`x~@`) beside it. Guesses, patterns and their times are space-separated in CSV and lists in JSON.
+     * `T` holds words and `N` numbers.
+     */
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    struct Row<T, N> {
+        date: String,
+        time: i64,
+        secret: String,
+        len: usize,
+        max_guesses: usize,
+        won: bool,
+        hard: bool,
+        hints: usize,
+        puzzle: Option<u32>,
+        elapsed_ms: Option<u64>,
+        time_limit: Option<u64>,
+        guesses: T,
+        patterns: T,
+        /// Milliseconds on the clock at each guess.
+        guess_ms: N,
+    }
+
+    impl<T, N> Row<T, N> {
+        fn map<U, M>(self, f: impl Fn(T) -> U, g: impl FnOnce(N) -> M) -> Row<U, M> {
+            Row {
+                date: self.date,
+                time: self.time,
+                secret: self.secret,
+                len: self.len,
+                max_guesses: self.max_guesses,
+                won: self.won,
+                hard: self.hard,
+                hints: self.hints,
+                puzzle: self.puzzle,
+                elapsed_ms: self.elapsed_ms,
+                time_limit: self.time_limit,
+                guesses: f(self.guesses),
+                patterns: f(self.patterns),
+                guess_ms: g(self.guess_ms),
+            }
+        }
+    }
+
+    fn row(record: &Record) -> Row<Vec<String>, Vec<u64>> {
+        Row {
+            date: record.date.clone(),
+            time: record.time,
+            secret: record.secret.clone(),
+            len: record.len,
+            max_guesses: record.max_guesses,
+            won: record.won,
+            hard: record.hard,
+            hints: record.hints,
+            puzzle: record.puzzle,
+            elapsed_ms: record.elapsed_ms,
+            time_limit: record.time_limit,
+            guess_ms: record.guess_ms.clone(),
+            patterns: record
+                .guesses
+                .iter()
+                .map(|g| join(&compare(&record.secret, g), true))
+                .collect(),
+            guesses: record.guesses.clone(),
+        }
+    }
+
+    fn record(row: Row<Vec<String>, Vec<u64>>) -> Record {
+        Record {
+            date: row.date,
+            time: row.time,
+            secret: row.secret,
+            len: row.len,
+            guesses: row.guesses,
+            max_guesses: row.max_guesses,
+            won: row.won,
+            hard: row.hard,
+            hints: row.hints,
+            puzzle: row.puzzle,
+            elapsed_ms: row.elapsed_ms,
+            guess_ms: row.guess_ms,
+            time_limit: row.time_limit,
+        }
+    }
+
+    fn date(date: &str) -> Result<NaiveDate, String> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| format!("Bad date {:?}: {}", date, e))
+    }
+
+    /// Writes every record from `since` (a YYYY-MM-DD date) on.
+    pub fn export(
+        output: &mut dyn Write,
+        records: &[Record],
+        format: Format,
+        since: Option<&str>,
+    ) -> Result<(), String> {
+        let since = since.map(date).transpose()?;
+        let rows = records
+            .iter()
+            .filter(|r| since.is_none_or(|since| date(&r.date).is_ok_and(|d| since <= d)))
+            .map(row);
+
+        match format {
+            Format::Json => {
+                let rows: Vec<_> = rows.collect();
+                serde_json::to_writer_pretty(&mut *output, &rows).map_err(|e| e.to_string())?;
+                writeln!(output).map_err(|e| e.to_string())
+            }
+            Format::Csv => {
+                let mut csv = csv::Writer::from_writer(output);
+                for r in rows {
+                    let ms = |ms: Vec<u64>| ms.iter().map(u64::to_string).collect::<Vec<_>>();
+                    csv.serialize(r.map(|v| v.join(" "), |v| ms(v).join(" ")))
+                        .map_err(|e| e.to_string())?;
+                }
+                csv.flush().map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    /**
+     * Reads records written by [export], in either format. A JSON stats file from another
+     * machine works too. Fails on the first record that doesn't add up, saying where it is.
+     */
+    pub fn parse(input: &mut dyn Read) -> Result<Vec<Record>, String> {
+        let mut text = String::new();
+        input.read_to_string(&mut text).map_err(|e| e.to_string())?;
+
+        if text.trim_start().starts_with(['[', '{']) {
+            let doc: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+            let records = if doc.is_object() {
+                crate::stats::store::migrate(doc)?
+            } else {
+                let rows: Vec<Row<Vec<String>, Vec<u64>>> =
+                    serde_json::from_value(doc).map_err(|e| e.to_string())?;
+                rows.into_iter().map(record).collect()
+            };
+            check_all(&records)?;
+            Ok(records)
+        } else {
+            let mut csv = csv::Reader::from_reader(text.as_bytes());
+            let headers = csv.headers().map_err(|e| e.to_string())?.clone();
+            csv.records()
+                .map(|r| {
+                    let r = r.map_err(|e| e.to_string())?;
+                    let line = r.position().map_or(0, |p| p.line());
+                    let at = |e: String| format!("Line {}: {}", line, e);
+                    let row: Row<String, String> = r
+                        .deserialize(Some(&headers))
+                        .map_err(|e| at(e.to_string()))?;
+                    let guess_ms = row
+                        .guess_ms
+                        .split_whitespace()
+                        .map(|t| t.parse().map_err(|_| at(format!("Bad guess time {:?}", t))))
+                        .collect::<Result<_, _>>()?;
+                    let record = record(row.map(
+                        |v| v.split_whitespace().map(String::from).collect(),
+                        |_| guess_ms,
+                    ));
+                    check(&record).map_err(at)?;
+                    Ok(record)
+                })
+                .collect()
+        }
+    }
+
+    /// Makes sure a record could have come from a real game.
+    fn check(record: &Record) -> Result<(), String> {
+        let len = record.secret.len();
+        if len == 0 || record.len != len {
+            return Err(format!(
+                "Length {} doesn't match the word {:?}.",
+                record.len, record.secret
+            ));
+        }
+        if let Some(g) = record.guesses.iter().find(|g| g.len() != len) {
+            return Err(format!("Guess {:?} is not of length {}.", g, len));
+        }
+        if record.max_guesses == 0 || record.max_guesses < record.guesses.len() {
+            return Err(format!(
+                "{} guesses made with {} allowed.",
+                record.guesses.len(),
+                record.max_guesses
+            ));
+        }
+        if record.won && record.guesses.last() != Some(&record.secret) {
+            return Err("Won without guessing the word.".to_string());
+        }
+        Ok(())
+    }
+
+    /// [check]s every record, numbering them from 1 in any error.
+    fn check_all(records: &[Record]) -> Result<(), String> {
+        for (i, r) in records.iter().enumerate() {
+            check(r).map_err(|e| format!("Game {}: {}", i + 1, e))?;
+        }
+        Ok(())
+    }
+
+    /// Adds the records the store doesn't already have. Returns how many were new.
+    pub fn import(store: &Store, records: Vec<Record>) -> Result<usize, String> {
+        check_all(&records)?;
+        let mut added = 0;
+        store.update(|existing| {
+            let mut seen: HashSet<(i64, String, Vec<String>)> = existing
+                .iter()
+                .map(|r| (r.time, r.secret.clone(), r.guesses.clone()))
+                .collect();
+            for r in records {
+                if seen.insert((r.time, r.secret.clone(), r.guesses.clone())) {
+                    existing.push(r);
+                    added += 1;
+                }
+            }
+        })?;
+        Ok(added)
+    }
+
+    #[test]
+    fn test_exchange() {
+        use crate::wordl::Game;
+
+        let mut game = Game::new("slump");
+        game.guess("plump").unwrap();
+        game.guess("slump").unwrap();
+        let mut old = Record::from_game(&game);
+        old.date = "2022-01-20".to_string();
+        old.time -= 1;
+        let records = vec![old, Record::from_game(&game)];
+
+        let mut csv = Vec::new();
+        export(&mut csv, &records, Format::Csv, None).unwrap();
+        let text = String::from_utf8(csv).unwrap();
+        assert!(text
+            .lines()
+            .nth(1)
+            .unwrap()
+            .contains(",PLUMP SLUMP,x@@@@ @@@@@,"));
+        assert_eq!(parse(&mut text.as_bytes()).unwrap(), records);
+
+        let mut json = Vec::new();
+        export(&mut json, &records, Format::Json, Some("2022-01-21")).unwrap();
+        assert_eq!(parse(&mut json.as_slice()).unwrap(), records[1..]);
+        let doc: Value = serde_json::from_slice(&json).unwrap();
+        assert!(doc[0]["guess_ms"][1].is_u64());
+
+        // Dates compare as dates, however they're written.
+        let mut json = Vec::new();
+        export(&mut json, &records, Format::Json, Some("2022-1-5")).unwrap();
+        assert_eq!(parse(&mut json.as_slice()).unwrap(), records);
+        assert!(export(&mut Vec::new(), &records, Format::Json, Some("Jan 21")).is_err());
+
+        // Rows that couldn't come from a real game are rejected, saying where they are.
+        let unguessed = text.replacen("PLUMP SLUMP", "", 1);
+        assert_eq!(
+            parse(&mut unguessed.as_bytes()),
+            Err("Line 2: Won without guessing the word.".to_string())
+        );
+        let mut too_many = records.clone();
+        too_many[1].max_guesses = 1;
+        assert!(
+            import(&Store::at(std::path::Path::new("/nonexistent")), too_many)
+                .unwrap_err()
+                .starts_with("Game 2:")
+        );
+
+        let dir = std::env::temp_dir().join(format!("wordle-rs-exchange-{}", std::process::id()));
+        let store = Store::at(&dir.join("stats.json"));
+        assert_eq!(import(&store, records[1..].to_vec()).unwrap(), 1);
+        assert_eq!(import(&store, records.clone()).unwrap(), 1);
+        assert_eq!(
+            store.load().unwrap(),
+            records[1..]
+                .iter()
+                .chain(&records[..1])
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+pub mod leaderboard {
+    use std::collections::HashMap;
+    use std::fmt::Write as _;
+    use std::fs::{self, File, OpenOptions};
+    use std::io::{BufRead, BufReader, Write};
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::dicts;
+    use crate::stats::store::Record;
+    use crate::wordl::clock;
+
+    /// One player's result for one daily puzzle.
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct Entry {
+        pub player: String,
+        pub puzzle: u32,
+        pub date: String,
+        /// Unix timestamp the game finished.
+        pub time: i64,
+        pub len: usize,
+        pub guesses: usize,
+        pub max_guesses: usize,
+        pub won: bool,
+        pub hard: bool,
+        pub hints: usize,
+        /// Milliseconds taken, if known.
+        #[serde(default)]
+        pub elapsed_ms: Option<u64>,
+        /// The dictionary the puzzle came from, since each picks a different word.
+        #[serde(default = "default_dict")]
+        pub dict: String,
+    }
+
+    /// Entries from before the dictionary was recorded used the default one.
+    fn default_dict() -> String {
+        dicts::NAMES[0].to_string()
+    }
+
+    impl Entry {
+        /// The entry for a finished game, if it was a daily puzzle.
+        pub fn of(player: &str, record: &Record) -> Option<Self> {
+            Some(Self {
+                player: player.to_string(),
+                puzzle: record.puzzle?,
+                date: record.date.clone(),
+                time: record.time,
+                len: record.len,
+                guesses: record.guesses.len(),
+                max_guesses: record.max_guesses,
+                won: record.won,
+                hard: record.hard,
+                hints: record.hints,
+                elapsed_ms: record.elapsed_ms,
+                dict: dicts::chosen().to_string(),
+            })
+        }
+
+        /// Guesses taken, counting a loss as one more than allowed.
+        fn cost(&self) -> usize {
+            if self.won {
+                self.guesses
+            } else {
+                self.max_guesses + 1
+            }
+        }
+    }
+
+    /// The player's login name, or `anonymous`.
+    pub fn whoami() -> String {
+        std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "anonymous".to_string())
+    }
+
+    /**
+     * A leaderboard shared by several players, e.g. on a network drive. Each daily result is
+     * appended as a JSON line while holding a lock on the file; only a player's first result
+     * for a puzzle counts.
+     */
+    #[derive(Debug, Clone)]
+    pub struct Board {
+        path: PathBuf,
+        player: String,
+    }
+
+    impl Board {
+        pub fn at(path: &Path, player: &str) -> Self {
+            Self {
+                path: path.to_path_buf(),
+                player: player.to_string(),
+            }
+        }
+
+        pub fn player(&self) -> &str {
+            &self.player
+        }
+
+        /// Appends the game if it was a daily puzzle this player hasn't posted yet.
+        pub fn post(&self, record: &Record) -> Result<bool, String> {
+            let entry = match Entry::of(&self.player, record) {
+                Some(entry) => entry,
+                None => return Ok(false),
+            };
+
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir).map_err(|e| self.err(e))?;
+            }
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .read(true)
+                .open(&self.path)
+                .map_err(|e| self.err(e))?;
+            file.lock().map_err(|e| self.err(e))?;
+
+            let posted = self.read(&file)?.iter().any(|e| {
+                (e.player.as_str(), e.puzzle, e.len, e.dict.as_str())
+                    == (&entry.player, entry.puzzle, entry.len, &entry.dict)
+            });
+            if posted {
+                return Ok(false);
+            }
+
+            let line = serde_json::to_string(&entry).map_err(|e| self.err(e))?;
+            writeln!(file, "{}", line).map_err(|e| self.err(e))?;
+            file.flush().map_err(|e| self.err(e))?;
+            Ok(true)
+        }
+
+        pub fn load(&self) -> Result<Vec<Entry>, String> {
+            let file = match File::open(&self.path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(self.err(e)),
+            };
+            file.lock_shared().map_err(|e| self.err(e))?;
+            self.read(&file)
+        }
+
+        fn read(&self, file: &File) -> Result<Vec<Entry>, String> {
+            BufReader::new(file)
+                .lines()
+                .filter(|l| l.as_ref().map_or(true, |l| !l.trim().is_empty()))
+                .map(|l| {
+                    let l = l.map_err(|e| self.err(e))?;
+                    serde_json::from_str(&l).map_err(|e| self.err(e))
+                })
+                .collect()
+        }
+
+        fn err(&self, e: impl std::fmt::Display) -> String {
+            format!("{}: {}", self.path.display(), e)
+        }
+    }
+
+    /// Each player's first result for `puzzle`, best first: fewest guesses, then quickest, then
+    /// soonest done.
+    pub fn day(entries: &[Entry], puzzle: u32, len: usize) -> Vec<&Entry> {
+        let mut firsts: HashMap<&str, &Entry> = HashMap::new();
+        for e in entries
+            .iter()
+            .filter(|e| e.puzzle == puzzle && e.len == len)
+        {
+            firsts
+                .entry(&e.player)
+                .and_modify(|f| {
+                    if e.time < f.time {
+                        *f = e
+                    }
+                })
+                .or_insert(e);
+        }
+
+        let mut ranked: Vec<&Entry> = firsts.into_values().collect();
+        ranked.sort_by_key(|e| {
+            (
+                !e.won,
+                e.guesses,
+                e.elapsed_ms.unwrap_or(u64::MAX),
+                e.time,
+                e.player.clone(),
+            )
+        });
+        ranked
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Standing {
+        pub player: String,
+        pub played: usize,
+        pub won: usize,
+        /// Guesses over all puzzles, counting a loss as one more than allowed.
+        pub guesses: usize,
+        /// Unix timestamp of the player's last finish.
+        pub time: i64,
+        pub hard: bool,
+    }
+
+    /**
+     * Totals over the puzzles `from..=to`, best first: most wins, then fewest guesses, then
+     * soonest done. A player is flagged hard only if every game was in hard mode.
+     */
+    pub fn span(entries: &[Entry], from: u32, to: u32, len: usize) -> Vec<Standing> {
+        let mut standings: HashMap<String, Standing> = HashMap::new();
+        for puzzle in from..=to {
+            for e in day(entries, puzzle, len) {
+                let s = standings
+                    .entry(e.player.clone())
+                    .or_insert_with(|| Standing {
+                        player: e.player.clone(),
+                        played: 0,
+                        won: 0,
+                        guesses: 0,
+                        time: 0,
+                        hard: true,
+                    });
+                s.played += 1;
+                s.won += e.won as usize;
+                s.guesses += e.cost();
+                s.time = s.time.max(e.time);
+                s.hard &= e.hard;
+            }
+        }
+
+        let mut ranked: Vec<Standing> = standings.into_values().collect();
+        ranked.sort_by_key(|s| {
+            (
+                std::cmp::Reverse(s.won),
+                s.guesses,
+                s.time,
+                s.player.clone(),
+            )
+        });
+        ranked
+    }
+
+    fn time_of_day(time: i64) -> String {
+        use chrono::TimeZone;
+        chrono::Local
+            .timestamp_opt(time, 0)
+            .single()
+            .map_or_else(String::new, |t| t.format("%H:%M").to_string())
+    }
+
+    pub fn render_day(ranked: &[&Entry], puzzle: u32) -> String {
+        let mut out = format!("Puzzle #{} (* hard mode)\n", puzzle);
+        let width = ranked.iter().map(|e| e.player.len()).max().unwrap_or(0);
+        for (i, e) in ranked.iter().enumerate() {
+            let score = if e.won {
+                e.guesses.to_string()
+            } else {
+                "X".to_string()
+            };
+            writeln!(
+                out,
+                "{:>2}. {:w$}  {}/{}{:1}  {:>7}  at {}",
+                i + 1,
+                e.player,
+                score,
+                e.max_guesses,
+                if e.hard { "*" } else { "" },
+                e.elapsed_ms
+                    .map_or_else(String::new, |ms| clock(Duration::from_millis(ms))),
+                time_of_day(e.time),
+                w = width
+            )
+            .unwrap();
+        }
+        if ranked.is_empty() {
+            out.push_str("No results yet.\n");
+        }
+        out
+    }
+
+    pub fn render_span(ranked: &[Standing], from: u32, to: u32) -> String {
+        let mut out = format!("Puzzles #{}-#{} (* hard mode)\n", from, to);
+        let width = ranked.iter().map(|s| s.player.len()).max().unwrap_or(0);
+        for (i, s) in ranked.iter().enumerate() {
+            writeln!(
+                out,
+                "{:>2}. {:w$}  won {}/{}  {} guesses{}",
+                i + 1,
+                s.player,
+                s.won,
+                s.played,
+                s.guesses,
+                if s.hard { " *" } else { "" },
+                w = width
+            )
+            .unwrap();
+        }
+        if ranked.is_empty() {
+            out.push_str("No results yet.\n");
+        }
+        out
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        use crate::wordl::{Game, Rules};
+
+        let dir = std::env::temp_dir().join(format!("wordle-rs-board-{}", std::process::id()));
+        let path = dir.join("board.jsonl");
+        let play = |player: &str, guesses: usize, hard: bool| {
+            let game = Game::daily(
+                5,
+                7,
+                Rules {
+                    hard,
+                    ..Rules::default()
+                },
+            )
+            .unwrap();
+            let mut record = Record::from_game(&game);
+            record.guesses = vec!["MAPLE".to_string(); guesses];
+            record.won = guesses < 6;
+            Board::at(&path, player).post(&record).unwrap()
+        };
+
+        assert!(play("bob", 3, false));
+        assert!(play("alice", 3, true));
+        assert!(play("carol", 6, false));
+        assert!(!play("alice", 1, false));
+
+        let mut undaily = Record::from_game(&Game::new("slump"));
+        undaily.puzzle = None;
+        assert!(!Board::at(&path, "dave").post(&undaily).unwrap());
+
+        let mut entries = Board::at(&path, "x").load().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|e| e.dict == dicts::chosen()));
+        // Posted in the same second, so break the tie.
+        entries[1].time -= 1;
+        let ranked: Vec<&str> = day(&entries, 7, 5)
+            .iter()
+            .map(|e| e.player.as_str())
+            .collect();
+        assert_eq!(ranked, vec!["alice", "bob", "carol"]);
+        assert!(day(&entries, 8, 5).is_empty());
+
+        let week = span(&entries, 1, 7, 5);
+        assert_eq!(week[0].player, "alice");
+        assert!(week[0].hard && !week[1].hard);
+        assert_eq!((week[2].won, week[2].guesses), (0, 7));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+pub mod profile {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::stats::store::Store;
+
+    /**
+     * A named player, with their own stats and config under `<root>/<name>/`. Without one, the
+     * shared default stats and config are used.
+     */
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Profile {
+        name: String,
+        dir: PathBuf,
+    }
+
+    impl Profile {
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        pub fn dir(&self) -> &Path {
+            &self.dir
+        }
+
+        pub fn stats(&self) -> Store {
+            Store::at(&self.dir.join("stats.json"))
+        }
+
+        pub fn config_path(&self) -> PathBuf {
+            self.dir.join("config.toml")
+        }
+    }
+
+    /// Every profile, as directories under one root.
+    #[derive(Debug, Clone)]
+    pub struct Profiles {
+        root: PathBuf,
+    }
+
+    impl Profiles {
+        pub fn at(root: &Path) -> Self {
+            Self {
+                root: root.to_path_buf(),
+            }
+        }
+
+        /// Under the XDG data directory, e.g. `~/.local/share/wordle-rs/profiles`.
+        pub fn default_root() -> Option<PathBuf> {
+            dirs::data_dir().map(|d| d.join("wordle-rs").join("profiles"))
+        }
+
+        /// The names of the profiles that exist, sorted.
+        pub fn list(&self) -> Result<Vec<String>, String> {
+            let entries = match fs::read_dir(&self.root) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(self.err(e)),
+            };
+
+            let mut names = Vec::new();
+            for entry in entries {
+                let entry = entry.map_err(|e| self.err(e))?;
+                if entry.path().is_dir() {
+                    if let Some(name) = entry.file_name().to_str() {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+            names.sort();
+            Ok(names)
+        }
+
+        /// The profile called `name`, whether or not it exists yet.
+        pub fn get(&self, name: &str) -> Result<Profile, String> {
+            check_name(name)?;
+            Ok(Profile {
+                name: name.to_string(),
+                dir: self.root.join(name),
+            })
+        }
+
+        /// Like [Profiles::get], but the profile must exist.
+        pub fn existing(&self, name: &str) -> Result<Profile, String> {
+            let profile = self.get(name)?;
+            if !profile.dir.is_dir() {
+                return Err(format!("There's no profile called {:?}.", name));
+            }
+            Ok(profile)
+        }
+
+        pub fn create(&self, name: &str) -> Result<Profile, String> {
+            let profile = self.get(name)?;
+            if profile.dir.exists() {
+                return Err(format!("There's already a profile called {:?}.", name));
+            }
+            fs::create_dir_all(&profile.dir).map_err(|e| self.err(e))?;
+            Ok(profile)
+        }
+
+        pub fn rename(&self, from: &str, to: &str) -> Result<Profile, String> {
+            let from = self.existing(from)?;
+            let to = self.get(to)?;
+            if to.dir.exists() {
+                return Err(format!("There's already a profile called {:?}.", to.name));
+            }
+            fs::rename(&from.dir, &to.dir).map_err(|e| self.err(e))?;
+            Ok(to)
+        }
+
+        /// Deletes the profile along with its stats and config.
+        pub fn delete(&self, name: &str) -> Result<(), String> {
+            let profile = self.existing(name)?;
+            fs::remove_dir_all(&profile.dir).map_err(|e| self.err(e))
+        }
+
+        fn err(&self, e: impl std::fmt::Display) -> String {
+            format!("{}: {}", self.root.display(), e)
+        }
+    }
+
+    /// Names become directory names, so keep them tame.
+    fn check_name(name: &str) -> Result<(), String> {
+        let tame = name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+        if name.is_empty() || !tame || name.starts_with('-') {
+            return Err(format!(
+                "{:?} can't be a profile name. Use letters, digits, - and _.",
+                name
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_profiles() {
+        let dir = std::env::temp_dir().join(format!("wordle-rs-profiles-{}", std::process::id()));
+        let profiles = Profiles::at(&dir);
+        assert_eq!(profiles.list(), Ok(vec![]));
+
+        let alice = profiles.create("alice").unwrap();
+        profiles.create("bob").unwrap();
+        assert!(profiles.create("bob").is_err());
+        assert!(profiles.get("../bob").is_err());
+        assert_eq!(profiles.list().unwrap(), vec!["alice", "bob"]);
+
+        alice
+            .stats()
+            .record(crate::stats::store::Record::from_game(
+                &crate::wordl::Game::new("slump"),
+            ))
+            .unwrap();
+        assert!(profiles.rename("alice", "bob").is_err());
+        let carol = profiles.rename("alice", "carol").unwrap();
+        assert_eq!(carol.stats().load().unwrap().len(), 1);
+        assert!(profiles.existing("alice").is_err());
+
+        profiles.delete("bob").unwrap();
+        assert_eq!(profiles.list().unwrap(), vec!["carol"]);
+        assert!(profiles.delete("bob").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
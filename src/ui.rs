@@ -3,10 +3,16 @@ pub mod term {
 
     use core::time::Duration;
     use crossterm::{
-        cursor::{position as crossterm_position, MoveTo, RestorePosition, SavePosition},
-        event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers},
+        cursor::{position as crossterm_position, MoveTo, RestorePosition, SavePosition, Show},
+        event::{
+            poll, read, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent,
+            KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+        },
         execute, queue,
-        style::{Color, Print, SetForegroundColor},
+        style::{
+            Attribute, Color, Print, SetAttribute, SetAttributes, SetBackgroundColor,
+            SetForegroundColor,
+        },
         terminal::{
             disable_raw_mode,
             enable_raw_mode,
@@ -16,9 +22,12 @@ pub mod term {
             ClearType,
         },
     };
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Once;
 
+    use crate::ui::backend::Backend;
     use crate::ui::position::{Position, Visible};
-    use crate::ui::screen::Screen;
+    use crate::ui::screen::{width, Screen, Style, CONTINUATION};
 
     macro_rules! ex {
     ( $( $x:expr ),* ) => {
@@ -59,6 +68,14 @@ pub mod term {
         ex!(Clear(ClearType::All), MoveTo(0, 0));
     }
 
+    /// Clears the backend and shows just `msg`, bypassing any [Screen] already drawn.
+    pub fn notice(backend: &mut dyn Backend, msg: &str) -> crossterm::Result<()> {
+        backend.clear()?;
+        let mut note = Screen::with_size(backend.size());
+        note.writes(&(0, 0).into(), msg);
+        backend.draw(&mut note)
+    }
+
     pub fn get_size() -> Position {
         crossterm_size().unwrap_or((0, 0)).into()
     }
@@ -71,44 +88,127 @@ pub mod term {
         Screen::with_size(get_size())
     }
 
-    pub fn event_loop<F>(mut handle_event: F) -> crossterm::Result<()>
-    where
-        F: FnMut(Position, Res) -> Res,
-    {
-        enable_raw_mode()?;
+    /// A terminal mode that [Guard]s share: entered by the first and left when the last goes.
+    struct Mode {
+        guards: AtomicUsize,
+        enter: fn() -> crossterm::Result<()>,
+        leave: fn(),
+        panic_hook: Once,
+    }
 
-        // Fire off initial event to zero the cursor.
-        if let Res::Move(dp) = handle_event((0, 0).into(), Res::None) {
-            let np: Visible = (dp).into();
-            ex!(MoveTo(np.0, np.1))
+    static RAW: Mode = Mode {
+        guards: AtomicUsize::new(0),
+        enter: enable_raw_mode,
+        leave: restore,
+        panic_hook: Once::new(),
+    };
+    static MOUSE: AtomicBool = AtomicBool::new(false);
+
+    /**
+     * Keeps the terminal in raw mode while alive. Dropping it, including while unwinding, or
+     * panicking with one alive puts the terminal back how the shell expects it: cooked, with the
+     * cursor showing and not capturing the mouse. Guards nest.
+     */
+    pub struct Guard {
+        mode: &'static Mode,
+    }
+
+    impl Guard {
+        pub fn raw() -> crossterm::Result<Self> {
+            Self::hold(&RAW)
         }
 
-        loop {
-            let size = get_size();
-            let cursor = get_position();
+        fn hold(mode: &'static Mode) -> crossterm::Result<Self> {
+            mode.panic_hook.call_once(|| {
+                let hook = std::panic::take_hook();
+                std::panic::set_hook(Box::new(move |info| {
+                    if 0 < mode.guards.load(Ordering::SeqCst) {
+                        (mode.leave)();
+                    }
+                    hook(info);
+                }));
+            });
+
+            if mode.guards.fetch_add(1, Ordering::SeqCst) == 0 {
+                if let Err(e) = (mode.enter)() {
+                    mode.guards.fetch_sub(1, Ordering::SeqCst);
+                    return Err(e);
+                }
+            }
+            Ok(Self { mode })
+        }
 
-            while !poll(Duration::from_millis(500))? {}
+        /// Like [Guard::raw], reporting mouse clicks as events until dropped.
+        pub fn mouse() -> crossterm::Result<Self> {
+            let guard = Self::raw()?;
+            execute!(stdout(), EnableMouseCapture)?;
+            MOUSE.store(true, Ordering::SeqCst);
+            Ok(guard)
+        }
+    }
 
-            if let Event::Key(event) = read()? {
-                let result: Res = key_event_to_res(event, size, cursor);
-                let handled = handle_event(cursor, result);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            if self.mode.guards.fetch_sub(1, Ordering::SeqCst) == 1 {
+                (self.mode.leave)();
+            }
+        }
+    }
 
-                match handled {
-                    Res::Move(dp) => {
-                        let np: Visible = (cursor + dp).into();
-                        ex!(MoveTo(np.0, np.1));
-                    }
-                    Res::QuitAt(p) => {
-                        let np: Visible = p.into();
-                        ex!(MoveTo(np.0, np.1));
-                        break
-                    },
-                    Res::Quit => break,
-                    _ => {}
+    /// Best-effort, since it runs when things have already gone wrong.
+    fn restore() {
+        let mut stdout = stdout();
+        if MOUSE.swap(false, Ordering::SeqCst) {
+            let _ = queue!(stdout, DisableMouseCapture);
+        }
+        let _ = execute!(stdout, SetAttribute(Attribute::Reset), Show);
+        let _ = disable_raw_mode();
+    }
+
+    /// How long [event_loop] waits for a key before sending [Res::Tick] instead.
+    pub const TICK: Duration = Duration::from_millis(100);
+
+    /**
+     * Feeds events from `backend` through `handle_event`, along with where the cursor is, until it
+     * asks to quit. [Res::Tick] is sent when nothing happens for a [TICK].
+     */
+    pub fn event_loop<F>(backend: &mut dyn Backend, mut handle_event: F) -> crossterm::Result<()>
+    where
+        F: FnMut(&mut dyn Backend, Position, Res) -> Res,
+    {
+        // Fire off initial event to zero the cursor.
+        if let Res::Move(dp) = handle_event(backend, (0, 0).into(), Res::None) {
+            backend.move_to(dp)?;
+        }
+
+        loop {
+            let size = backend.size();
+            let cursor = backend.cursor();
+
+            let result = match backend.event(TICK)? {
+                Some(Event::Key(event)) => key_event_to_res(event, size, cursor),
+                Some(Event::Resize(cols, rows)) => Res::Resize((cols, rows).into()),
+                Some(Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row,
+                    ..
+                })) => Res::Click((column, row).into()),
+                Some(_) => continue,
+                None => Res::Tick,
+            };
+            let handled = handle_event(backend, cursor, result);
+
+            match handled {
+                Res::Move(dp) => backend.move_to(cursor + dp)?,
+                Res::QuitAt(p) => {
+                    backend.move_to(p)?;
+                    break;
                 }
+                Res::Quit => break,
+                _ => {}
             }
         }
-        disable_raw_mode()?;
 
         Ok(())
     }
@@ -190,7 +290,7 @@ pub mod term {
         let mut screen = Screen::with_size(get_size());
         let mut res: Res = Res::None;
 
-        enable_raw_mode()?;
+        let _guard = Guard::raw()?;
         loop {
             let size = get_size();
             let cursor = get_position();
@@ -237,12 +337,11 @@ pub mod term {
                     Res::Write(ch) => {
                         screen.write(&cursor, ch);
                     }
-                    Res::Quit|Res::QuitAt(_) => break,
-                    Res::None => {}
+                    Res::Quit | Res::QuitAt(_) => break,
+                    Res::Tick | Res::Resize(_) | Res::Click(_) | Res::None => {}
                 }
             }
         }
-        disable_raw_mode()?;
 
         Ok(())
     }
@@ -255,9 +354,58 @@ pub mod term {
         Backspace,
         Quit,
         QuitAt(Position),
+        /// No key was pressed for a [TICK].
+        Tick,
+        /// The terminal is now this many columns and rows.
+        Resize(Position),
+        /// The left mouse button went down here. See [Targets].
+        Click(Position),
         None,
     }
 
+    /// Clickable areas of the screen, and what clicking each one means.
+    #[derive(Debug, Default, Clone)]
+    pub struct Targets {
+        areas: Vec<(Position, Position, Res)>,
+    }
+
+    impl Targets {
+        /// Clicks from `start` to `end`, inclusive, mean `res`. Later areas win where they overlap.
+        pub fn add(&mut self, start: Position, end: Position, res: Res) {
+            self.areas.push((start, end, res));
+        }
+
+        /// Like [Targets::add], for `text` written at `start` on one row.
+        pub fn add_text(&mut self, start: Position, text: &str, res: Res) {
+            let end = start + (text.chars().count() as i32 - 1, 0).into();
+            self.add(start, end, res);
+        }
+
+        pub fn hit(&self, at: Position) -> Option<Res> {
+            self.areas
+                .iter()
+                .rev()
+                .find(|(start, end, _)| {
+                    (start.col..=end.col).contains(&at.col)
+                        && (start.row..=end.row).contains(&at.row)
+                })
+                .map(|&(_, _, res)| res)
+        }
+    }
+
+    #[test]
+    fn test_targets() {
+        let mut targets = Targets::default();
+        targets.add_text((2, 1).into(), "ENTER", Res::Enter);
+        targets.add((0, 0).into(), (9, 0).into(), Res::Write('a'));
+        targets.add((4, 0).into(), (4, 0).into(), Res::Write('b'));
+
+        assert!(matches!(targets.hit((6, 1).into()), Some(Res::Enter)));
+        assert!(targets.hit((7, 1).into()).is_none());
+        assert!(matches!(targets.hit((3, 0).into()), Some(Res::Write('a'))));
+        assert!(matches!(targets.hit((4, 0).into()), Some(Res::Write('b'))));
+    }
+
     pub fn process_event(
         screen: &mut Screen,
         event: KeyEvent,
@@ -366,51 +514,111 @@ pub mod term {
         }
     }
 
+    /// Draws `screen` to stdout, in raw mode if stdout is a terminal.
     pub fn dump_screen(screen: &mut Screen) -> crossterm::Result<()> {
-        enable_raw_mode()?;
-        just_dump_screen(screen)?;
-        disable_raw_mode()?;
-
-        Ok(())
+        let _guard = if stdout().is_terminal() {
+            Some(Guard::raw()?)
+        } else {
+            None
+        };
+        just_dump_screen(screen)
     }
 
     pub fn just_dump_screen(screen: &mut Screen) -> crossterm::Result<()> {
-        let mut stdout = stdout();
-        queue!(stdout, SavePosition)?;
+        draw_screen(&mut stdout(), screen)
+    }
 
-        // Iterate through the characters that need to be printed in order.
-        // This ensures that byte offests for non-ASCII characters are applied correctly.
-        let mut updates = screen.flush();
-        updates.sort_by_key(|&(pos, _)| pos);
+    /// Draws what changed in `screen` to `out`, leaving the cursor where it was.
+    fn draw_screen(out: &mut impl Write, screen: &mut Screen) -> crossterm::Result<()> {
+        queue!(out, SavePosition)?;
 
-        // Whenever a multi-byte character is printed, the caracters after it need
-        // to have their cursor position shifted by the excess amount.
-        let mut byte_offset: u16 = 0;
-        let mut row_for_offset: i32 = 0;
+        // Cells are terminal columns, so there's no need to go in order, but it keeps moves short.
+        let mut updates = screen.flush();
+        updates.sort_by_key(|&(pos, _, _)| pos);
 
-        for (postion, ch) in updates {
-            let clamped = screen.clamp(&postion);
+        // Only switch colors when they change, so a row of tiles is one style change per tile.
+        let mut current = Style::default();
 
-            if row_for_offset != clamped.row {
-                row_for_offset = clamped.row;
-                byte_offset = 0;
+        for (postion, grapheme, style) in updates {
+            // Printing a wide character covers its continuation too.
+            if grapheme.starts_with(CONTINUATION) {
+                continue;
             }
-
-            let casted: Visible = clamped.into();
-            let offsetted = (casted.0 + byte_offset, casted.1);
-            queue!(stdout, MoveTo(offsetted.0, offsetted.1), Print(ch))?;
-
-            if 1 < ch.len_utf16() {
-                byte_offset += 1;
+            // Half of it would hang off the edge.
+            let grapheme = if width(&grapheme) == 2 && screen.cols <= postion.col + 1 {
+                BLANK.to_string()
+            } else {
+                grapheme
+            };
+
+            let casted: Visible = screen.clamp(&postion).into();
+            if style != current {
+                queue!(out, SetAttribute(Attribute::Reset))?;
+                if let Some(fg) = style.fg {
+                    queue!(out, SetForegroundColor(fg))?;
+                }
+                if let Some(bg) = style.bg {
+                    queue!(out, SetBackgroundColor(bg))?;
+                }
+                if !style.attrs.is_empty() {
+                    queue!(out, SetAttributes(style.attrs))?;
+                }
+                current = style;
             }
+            queue!(out, MoveTo(casted.0, casted.1), Print(grapheme))?;
         }
 
-        queue!(stdout, RestorePosition)?;
-        stdout.flush()?;
+        if current != Style::default() {
+            queue!(out, SetAttribute(Attribute::Reset))?;
+        }
+        queue!(out, RestorePosition)?;
+        out.flush()?;
 
         Ok(())
     }
 
+    /**
+     * Asks the terminal to put `text` on the system clipboard (OSC 52). Terminals that don't
+     * support it ignore the sequence, so this is best-effort.
+     */
+    pub fn copy(output: &mut dyn Write, text: &str) {
+        write!(output, "\x1b]52;c;{}\x07", base64(text.as_bytes())).unwrap();
+        output.flush().unwrap();
+    }
+
+    fn base64(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_copy() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"Man"), "TWFu");
+
+        let mut out = Vec::new();
+        copy(&mut out, "wordle-rs");
+        assert_eq!(out, b"\x1b]52;c;d29yZGxlLXJz\x07");
+    }
+
     #[test]
     fn test_dump() {
         let mut screen = Screen::default();
@@ -436,6 +644,71 @@ pub mod term {
         println!();
     }
 
+    #[test]
+    fn test_guard() {
+        // Stand-ins for raw mode, so this runs without a terminal.
+        static ENTERED: AtomicUsize = AtomicUsize::new(0);
+        static LEFT: AtomicUsize = AtomicUsize::new(0);
+        static FAKE: Mode = Mode {
+            guards: AtomicUsize::new(0),
+            enter: || {
+                ENTERED.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+            leave: || {
+                LEFT.fetch_add(1, Ordering::SeqCst);
+            },
+            panic_hook: Once::new(),
+        };
+        static BROKEN: Mode = Mode {
+            guards: AtomicUsize::new(0),
+            enter: || Err(std::io::Error::other("not a tty")),
+            leave: || panic!("Never entered."),
+            panic_hook: Once::new(),
+        };
+        let counts = || (ENTERED.load(Ordering::SeqCst), LEFT.load(Ordering::SeqCst));
+
+        // Guards nest: only the outermost enters and leaves.
+        let outer = Guard::hold(&FAKE).unwrap();
+        drop(Guard::hold(&FAKE).unwrap());
+        assert_eq!(counts(), (1, 0));
+        drop(outer);
+        assert_eq!(counts(), (1, 1));
+
+        // A panic leaves before the panic message, and again as the guard unwinds.
+        let panicked = std::panic::catch_unwind(|| {
+            let _guard = Guard::hold(&FAKE).unwrap();
+            panic!("Restoring the terminal after this.");
+        });
+        assert!(panicked.is_err());
+        assert_eq!(counts(), (2, 3));
+        assert_eq!(FAKE.guards.load(Ordering::SeqCst), 0);
+
+        // A mode that can't be entered isn't held.
+        assert!(Guard::hold(&BROKEN).is_err());
+        assert_eq!(BROKEN.guards.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_draw_wide() {
+        let mut screen = Screen::with_size((10, 2).into());
+        screen.writes(&(0, 0).into(), "a🟩b");
+        screen.write(&(9, 1).into(), '漢');
+        let mut out = Vec::new();
+        draw_screen(&mut out, &mut screen).unwrap();
+        let drawn = String::from_utf8(out).unwrap();
+        // Every cell goes where it's kept, and a wide one that doesn't fit is left blank.
+        assert!(drawn.contains("\x1b[1;1Ha\x1b[1;2H🟩\x1b[1;4Hb\x1b[2;10H "));
+
+        // Sparse updates land in the right column too, with any marks on them.
+        screen.writes(&(3, 0).into(), "ce\u{301}");
+        let mut out = Vec::new();
+        draw_screen(&mut out, &mut screen).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("\x1b[1;4Hc\x1b[1;5He\u{301}\x1b"));
+    }
+
     pub fn e() {
         enable_raw_mode().unwrap();
     }
@@ -454,6 +727,173 @@ pub mod term {
     }
 }
 
+pub mod backend {
+    use std::collections::VecDeque;
+    use std::io::stdout;
+    use std::time::Duration;
+
+    use crossterm::{
+        cursor::MoveTo,
+        event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers},
+        execute,
+        terminal::{Clear, ClearType},
+    };
+
+    use crate::ui::position::{Position, Visible};
+    use crate::ui::screen::{Screen, CONTINUATION};
+    use crate::ui::term::{self, Guard};
+
+    /// Where [term::event_loop] gets its events from and draws to.
+    pub trait Backend {
+        /// Columns and rows.
+        fn size(&mut self) -> Position;
+        fn cursor(&mut self) -> Position;
+        fn move_to(&mut self, at: Position) -> crossterm::Result<()>;
+        /// Blanks everything and puts the cursor top left.
+        fn clear(&mut self) -> crossterm::Result<()>;
+        /// Flushes `screen`, showing whatever changed since it was last drawn.
+        fn draw(&mut self, screen: &mut Screen) -> crossterm::Result<()>;
+        /// The next event, or `None` if there isn't one within `timeout`.
+        fn event(&mut self, timeout: Duration) -> crossterm::Result<Option<Event>>;
+    }
+
+    /// The real terminal, raw and capturing the mouse for as long as this lives.
+    pub struct Crossterm {
+        _guard: Guard,
+    }
+
+    impl Crossterm {
+        pub fn new() -> crossterm::Result<Self> {
+            Ok(Self {
+                _guard: Guard::mouse()?,
+            })
+        }
+    }
+
+    impl Backend for Crossterm {
+        fn size(&mut self) -> Position {
+            term::get_size()
+        }
+
+        fn cursor(&mut self) -> Position {
+            term::get_position()
+        }
+
+        fn move_to(&mut self, at: Position) -> crossterm::Result<()> {
+            let at: Visible = at.into();
+            execute!(stdout(), MoveTo(at.0, at.1))
+        }
+
+        fn clear(&mut self) -> crossterm::Result<()> {
+            execute!(stdout(), Clear(ClearType::All), MoveTo(0, 0))
+        }
+
+        fn draw(&mut self, screen: &mut Screen) -> crossterm::Result<()> {
+            term::just_dump_screen(screen)
+        }
+
+        fn event(&mut self, timeout: Duration) -> crossterm::Result<Option<Event>> {
+            if poll(timeout)? {
+                read().map(Some)
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
+    /**
+     * A pretend terminal for tests. Events come from a script, pressing Ctrl-C once it runs out,
+     * and what was drawn can be read back from [Memory::screen].
+     */
+    pub struct Memory {
+        shown: Screen,
+        cursor: Position,
+        events: VecDeque<Event>,
+    }
+
+    impl Memory {
+        pub fn new(size: Position, events: impl IntoIterator<Item = Event>) -> Self {
+            Self {
+                shown: Screen::with_size(size),
+                cursor: (0, 0).into(),
+                events: events.into_iter().collect(),
+            }
+        }
+
+        /// Types `keys`, with `\r` for Enter, `\x7f` for Backspace and `\x03` for Ctrl-C.
+        pub fn typing(size: Position, keys: &str) -> Self {
+            let events = keys.chars().map(|ch| {
+                let (code, modifiers) = match ch {
+                    '\r' => (KeyCode::Enter, KeyModifiers::NONE),
+                    '\x7f' => (KeyCode::Backspace, KeyModifiers::NONE),
+                    '\x03' => (KeyCode::Char('c'), KeyModifiers::CONTROL),
+                    ch => (KeyCode::Char(ch), KeyModifiers::NONE),
+                };
+                Event::Key(KeyEvent::new(code, modifiers))
+            });
+            Self::new(size, events)
+        }
+
+        pub fn screen(&self) -> &Screen {
+            &self.shown
+        }
+
+        /// Every row as shown, without trailing blanks.
+        pub fn lines(&self) -> Vec<String> {
+            (0..self.shown.rows)
+                .map(|row| {
+                    let line: String = (0..self.shown.cols)
+                        .map(|col| self.shown.grapheme(&(col, row).into()))
+                        .filter(|grapheme| !grapheme.starts_with(CONTINUATION))
+                        .collect();
+                    line.trim_end().to_string()
+                })
+                .collect()
+        }
+    }
+
+    impl Backend for Memory {
+        fn size(&mut self) -> Position {
+            (self.shown.cols, self.shown.rows).into()
+        }
+
+        fn cursor(&mut self) -> Position {
+            self.cursor
+        }
+
+        fn move_to(&mut self, at: Position) -> crossterm::Result<()> {
+            let last = Position::new(self.shown.cols - 1, self.shown.rows - 1);
+            self.cursor = at.clamp((0, 0).into(), last);
+            Ok(())
+        }
+
+        fn clear(&mut self) -> crossterm::Result<()> {
+            self.shown = Screen::with_size(self.size());
+            self.cursor = (0, 0).into();
+            Ok(())
+        }
+
+        fn draw(&mut self, screen: &mut Screen) -> crossterm::Result<()> {
+            for (at, grapheme, style) in screen.flush() {
+                self.shown.write_grapheme(&at, &grapheme, style);
+            }
+            self.shown.flush();
+            Ok(())
+        }
+
+        fn event(&mut self, _timeout: Duration) -> crossterm::Result<Option<Event>> {
+            let event = self.events.pop_front().unwrap_or_else(|| {
+                Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL))
+            });
+            if let Event::Resize(cols, rows) = event {
+                self.shown.resize((cols, rows).into());
+                self.shown.flush();
+            }
+            Ok(Some(event))
+        }
+    }
+}
+
 pub mod position {
     use core::cmp::Ordering;
     use core::fmt;
@@ -612,16 +1052,48 @@ pub mod position {
 pub mod screen {
     use std::collections::HashMap;
 
+    use crossterm::style::{Attributes, Color};
+    use unicode_segmentation::UnicodeSegmentation;
+    use unicode_width::UnicodeWidthStr;
+
     use crate::ui::position::Position;
 
     pub const BLANK: char = ' ';
+    /// Fills the cell right of a wide character, which covers both on the terminal.
+    pub const CONTINUATION: char = '\0';
+
+    /**
+     * How many columns `grapheme` takes up on the terminal: 0, as for a lone combining mark, 1
+     * or 2. Emoji sequences, like `❤️` or `👨‍👩‍👧`, are as wide as the emoji they show.
+     */
+    pub fn width(grapheme: &str) -> i32 {
+        if grapheme.starts_with(CONTINUATION) {
+            0
+        } else {
+            grapheme.width().min(2) as i32
+        }
+    }
+
+    /// How a cell is colored. `None` leaves the terminal's own color.
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+    pub struct Style {
+        pub fg: Option<Color>,
+        pub bg: Option<Color>,
+        /// Bold, underlined and so on.
+        pub attrs: Attributes,
+    }
 
+    /**
+     * A grid of cells, one per terminal column, each holding a grapheme: a character and any
+     * combining marks on it. A wide one, like most emoji and CJK, takes its cell and the next,
+     * which holds [CONTINUATION].
+     */
     #[derive(Debug)]
     pub struct Screen {
         pub cols: i32,
         pub rows: i32,
-        written: HashMap<Position, char>,
-        buffer: HashMap<Position, char>,
+        written: HashMap<Position, (String, Style)>,
+        buffer: HashMap<Position, (String, Style)>,
     }
 
     impl Default for Screen {
@@ -643,45 +1115,151 @@ pub mod screen {
         /**
          * Inserts the character into the buffer. Until the screen is [#flush]-ed the written
          * value will not be returned by [#read]. Returns the character that was previously
-         * buffered, or [BLANK].
+         * buffered, or [BLANK]. Zero-width characters, like combining marks, go on the cell to
+         * the left.
          */
         pub fn write(&mut self, position: &Position, ch: char) -> char {
-            self.buffer.insert(*position, ch).unwrap_or(BLANK)
+            self.write_styled(position, ch, Style::default())
+        }
+
+        /// Like [Screen::write], but colored.
+        pub fn write_styled(&mut self, position: &Position, ch: char, style: Style) -> char {
+            let prev = self.write_grapheme(position, ch.encode_utf8(&mut [0; 4]), style);
+            prev.chars().next().unwrap_or(BLANK)
+        }
+
+        /// Like [Screen::write_styled], for a whole grapheme. Returns the one it replaced.
+        pub fn write_grapheme(
+            &mut self,
+            position: &Position,
+            grapheme: &str,
+            style: Style,
+        ) -> String {
+            let width = width(grapheme);
+            if width == 0 {
+                // Continuations come with the wide character they belong to.
+                if !grapheme.starts_with(CONTINUATION) {
+                    self.mark(position, grapheme);
+                }
+                return BLANK.to_string();
+            }
+
+            for col in 0..width {
+                self.split(*position + (col, 0).into());
+            }
+            if width == 2 {
+                let next = *position + (1, 0).into();
+                self.buffer.insert(next, (CONTINUATION.to_string(), style));
+            }
+            self.buffer
+                .insert(*position, (grapheme.to_string(), style))
+                .map_or(BLANK.to_string(), |(grapheme, _)| grapheme)
+        }
+
+        /// Adds zero-width `marks` to the grapheme left of `position`, if there is one.
+        fn mark(&mut self, position: &Position, marks: &str) {
+            let mut at = *position - (1, 0).into();
+            if self.cell(&at).0.starts_with(CONTINUATION) {
+                at = at - (1, 0).into();
+            }
+            let (mut grapheme, style) = self.cell(&at);
+            if grapheme != BLANK.to_string() {
+                grapheme.push_str(marks);
+                self.buffer.insert(at, (grapheme, style));
+            }
+        }
+
+        /// Blanks the other half of any wide character at `position`, which is about to change.
+        fn split(&mut self, position: Position) {
+            let (grapheme, _) = self.cell(&position);
+            let other = if grapheme.starts_with(CONTINUATION) {
+                position - (1, 0).into()
+            } else if width(&grapheme) == 2 {
+                position + (1, 0).into()
+            } else {
+                return;
+            };
+            self.buffer
+                .insert(other, (BLANK.to_string(), Style::default()));
+        }
+
+        /// What the cell will be after the next [Screen::flush].
+        fn cell(&self, position: &Position) -> (String, Style) {
+            self.buffer
+                .get(position)
+                .or_else(|| self.written.get(position))
+                .cloned()
+                .unwrap_or((BLANK.to_string(), Style::default()))
         }
 
         pub fn writes(&mut self, start: &Position, string: &str) -> String {
+            self.writes_styled(start, string, Style::default())
+        }
+
+        pub fn writes_styled(&mut self, start: &Position, string: &str, style: Style) -> String {
             let mut pos = *start;
             let mut replaced = String::new();
 
-            for ch in string.chars() {
-                if ch == '\n' {
+            for grapheme in string.graphemes(true) {
+                if grapheme == "\n" {
                     pos.col = 0;
                     pos.row += 1;
                 } else {
-                    let prev = self.write(&pos, ch);
-                    replaced.push(prev);
-                    pos.col += 1;
+                    let prev = self.write_grapheme(&pos, grapheme, style);
+                    if 0 < width(grapheme) {
+                        replaced.push_str(&prev);
+                        pos.col += width(grapheme);
+                    }
                 }
             }
 
             replaced
         }
 
+        /**
+         * Changes the size, then queues everything written to be drawn again at the next
+         * [Screen::flush], as after the terminal was cleared.
+         */
+        pub fn resize(&mut self, col_row: Position) {
+            self.cols = col_row.col;
+            self.rows = col_row.row;
+            self.redraw();
+        }
+
+        /// Queues everything written to be drawn again at the next [Screen::flush].
+        pub fn redraw(&mut self) {
+            for (pos, cell) in self.written.drain() {
+                self.buffer.entry(pos).or_insert(cell);
+            }
+        }
+
         pub fn clear(&mut self) {
             for &pos in self.written.keys() {
-                self.buffer.insert(pos, BLANK);
+                self.buffer
+                    .insert(pos, (BLANK.to_string(), Style::default()));
             }
         }
 
         /**
-         * Returns the character that has been written and flushed at the designated position.
-         * If the position has not been touched, [BLANK] is returned.
+         * Returns the character that has been written and flushed at the designated position,
+         * without any marks on it. If the position has not been touched, [BLANK] is returned.
          */
         pub fn read(&self, position: &Position) -> char {
-            match self.written.get(position) {
-                Some(&ch) => ch,
-                None => BLANK,
-            }
+            self.grapheme(position).chars().next().unwrap_or(BLANK)
+        }
+
+        /// The whole grapheme flushed at the position, like [Screen::read].
+        pub fn grapheme(&self, position: &Position) -> &str {
+            self.written
+                .get(position)
+                .map_or(" ", |(grapheme, _)| grapheme.as_str())
+        }
+
+        /// The style flushed at the position, like [Screen::read].
+        pub fn style(&self, position: &Position) -> Style {
+            self.written
+                .get(position)
+                .map_or(Style::default(), |(_, style)| *style)
         }
 
         pub fn reads(&self, start: &Position, end: &Position) -> String {
@@ -696,8 +1274,10 @@ pub mod screen {
                     continue;
                 }
 
-                let ch = self.read(&pos);
-                red.push(ch);
+                let grapheme = self.grapheme(&pos);
+                if !grapheme.starts_with(CONTINUATION) {
+                    red.push_str(grapheme);
+                }
                 pos.col += 1;
             }
 
@@ -708,18 +1288,16 @@ pub mod screen {
          * Flushes the buffered writes into the written state and returns copies of the elements
          * that were written.
          */
-        pub fn flush(&mut self) -> Vec<(Position, char)> {
-            let mut updates: Vec<(Position, char)> = Vec::with_capacity(self.buffer.capacity());
+        pub fn flush(&mut self) -> Vec<(Position, String, Style)> {
+            let mut updates: Vec<(Position, String, Style)> =
+                Vec::with_capacity(self.buffer.capacity());
 
-            for (&position, &ch) in self.buffer.iter() {
-                if self.clamp(&position) != position {
-                    // Out-of-bounds positions can be buffered, but the are ignored at flush.
-                    continue;
-                }
+            for (&position, cell) in self.buffer.iter() {
+                let original = self.written.insert(position, cell.clone());
 
-                let original = self.written.insert(position, ch);
-                if original == None || original != Some(ch) {
-                    updates.push((position, ch));
+                // Out-of-bounds positions are kept, for if the screen grows, but not drawn.
+                if self.clamp(&position) == position && original.as_ref() != Some(cell) {
+                    updates.push((position, cell.0.clone(), cell.1));
                 }
             }
 
@@ -783,4 +1361,523 @@ pub mod screen {
         assert_eq!(screen.read(&(0, 1).into()), 'h');
         assert_eq!(screen.read(&(1, 2).into()), 'i');
     }
+
+    #[test]
+    fn test_style() {
+        let mut screen = Screen::default();
+        let green = Style {
+            bg: Some(Color::Green),
+            ..Style::default()
+        };
+        screen.writes(&(0, 0).into(), "hi");
+        screen.flush();
+
+        // Restyling a character counts as a change, rewriting it the same way doesn't.
+        screen.write_styled(&(0, 0).into(), 'h', green);
+        screen.write(&(1, 0).into(), 'i');
+        assert_eq!(screen.flush(), vec![((0, 0).into(), "h".into(), green)]);
+        assert_eq!(screen.style(&(0, 0).into()), green);
+        assert_eq!(screen.style(&(1, 0).into()), Style::default());
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut screen = Screen::with_size((10, 10).into());
+        screen.writes(&(0, 0).into(), "hi");
+        screen.write(&(20, 0).into(), '!');
+        assert_eq!(screen.flush().len(), 2);
+
+        // Everything comes back after a resize, including what didn't fit before.
+        screen.resize((30, 10).into());
+        let mut out = screen.flush();
+        out.sort_by_key(|&(pos, _, _)| pos);
+        let chars: String = out.iter().map(|(_, ch, _)| ch.as_str()).collect();
+        assert_eq!(chars, "hi!");
+        assert_eq!(screen.read(&(20, 0).into()), '!');
+    }
+
+    #[test]
+    fn test_wide() {
+        let mut screen = Screen::default();
+        // Combining marks share the cell of the character they're on.
+        screen.writes(&(0, 0).into(), "a🟩b漢e\u{301}!");
+        screen.flush();
+        assert_eq!(
+            screen.reads(&(0, 0).into(), &(7, 0).into()),
+            "a🟩b漢e\u{301}!"
+        );
+        assert_eq!(screen.read(&(2, 0).into()), CONTINUATION);
+        assert_eq!(screen.read(&(6, 0).into()), 'e');
+        assert_eq!(screen.grapheme(&(6, 0).into()), "e\u{301}");
+        assert_eq!(screen.read(&(7, 0).into()), '!');
+
+        // Overwriting either half of a wide character blanks the other.
+        screen.write(&(2, 0).into(), 'x');
+        screen.write(&(4, 0).into(), 'y');
+        let mut out = screen.flush();
+        out.sort_by_key(|&(pos, _, _)| pos);
+        let chars: String = out.iter().map(|(_, ch, _)| ch.as_str()).collect();
+        assert_eq!(chars, " xy ");
+        assert_eq!(
+            screen.reads(&(0, 0).into(), &(7, 0).into()),
+            "a xby e\u{301}!"
+        );
+
+        // And a wide character splits any it lands on.
+        screen.writes(&(0, 1).into(), "ab漢");
+        screen.flush();
+        screen.write(&(1, 1).into(), '⬛');
+        assert_eq!(screen.flush().len(), 3);
+        assert_eq!(screen.reads(&(0, 1).into(), &(4, 1).into()), "a⬛  ");
+
+        // Emoji sequences are one wide character, and a mark written alone goes on its left.
+        screen.writes(&(0, 2).into(), "❤\u{fe0f}👨\u{200d}👩\u{200d}👧o");
+        screen.write(&(5, 2).into(), '\u{308}');
+        screen.flush();
+        assert_eq!(screen.grapheme(&(0, 2).into()), "❤\u{fe0f}");
+        assert_eq!(screen.read(&(1, 2).into()), CONTINUATION);
+        assert_eq!(screen.grapheme(&(2, 2).into()), "👨\u{200d}👩\u{200d}👧");
+        assert_eq!(screen.read(&(3, 2).into()), CONTINUATION);
+        assert_eq!(screen.grapheme(&(4, 2).into()), "o\u{308}");
+        assert_eq!(screen.read(&(5, 2).into()), BLANK);
+    }
+}
+
+pub mod animation {
+    use std::time::{Duration, Instant};
+
+    use crate::ui::position::Position;
+    use crate::ui::screen::{Screen, Style, BLANK};
+    use crate::ui::term::TICK;
+
+    /// A cell to draw: where, what and how.
+    pub type Cell = (Position, char, Style);
+
+    /// How long each frame shows. Frames are stepped on [TICK]s, so no shorter than one.
+    pub const FRAME: Duration = TICK;
+
+    /**
+     * Frames written into a [Screen] over time, each replacing the cells of the one before. The
+     * last frame is what the screen should be left showing.
+     */
+    #[derive(Debug)]
+    pub struct Animation {
+        frames: Vec<Vec<Cell>>,
+        started: Instant,
+        shown: Option<usize>,
+    }
+
+    impl Animation {
+        pub fn new(frames: Vec<Vec<Cell>>, started: Instant) -> Self {
+            Self {
+                frames,
+                started,
+                shown: None,
+            }
+        }
+
+        /// Tiles turning from `plain` to their final style one at a time, left to right.
+        pub fn reveal(tiles: &[Cell], plain: Style, started: Instant) -> Self {
+            let frames = (0..=tiles.len())
+                .map(|revealed| {
+                    tiles
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &(at, ch, style))| {
+                            (at, ch, if i < revealed { style } else { plain })
+                        })
+                        .collect()
+                })
+                .collect();
+            Self::new(frames, started)
+        }
+
+        /// A row sliding left and right, over `around`: what's either side of it.
+        pub fn shake(row: &[Cell], around: &[Cell], started: Instant) -> Self {
+            let frames = [1, -1, 1, -1, 0]
+                .iter()
+                .map(|&dx| {
+                    let mut frame: Vec<Cell> = around.to_vec();
+                    frame.extend(row.iter().map(|&(at, _, _)| (at, BLANK, Style::default())));
+                    // Whatever slides off the left edge is just cut off.
+                    frame.extend(
+                        row.iter()
+                            .map(|&(at, ch, style)| (at + (dx, 0).into(), ch, style))
+                            .filter(|(at, _, _)| 0 <= at.col),
+                    );
+                    frame
+                })
+                .collect();
+            Self::new(frames, started)
+        }
+
+        /// Each tile hopping up onto the row `above` in turn.
+        pub fn bounce(tiles: &[Cell], above: &[Cell], started: Instant) -> Self {
+            let frames = (0..=tiles.len())
+                .map(|hopping| {
+                    let mut frame: Vec<Cell> = above.iter().chain(tiles).copied().collect();
+                    if let Some(&(at, ch, style)) = tiles.get(hopping) {
+                        frame.push((at, BLANK, Style::default()));
+                        frame.push((at - (0, 1).into(), ch, style));
+                    }
+                    frame
+                })
+                .collect();
+            Self::new(frames, started)
+        }
+
+        /// Plays `next` once this is over.
+        pub fn then(mut self, next: Animation) -> Self {
+            self.frames.extend(next.frames);
+            self
+        }
+
+        /**
+         * Writes the next frame if it's due at `now`. Frames are never skipped, so if steps come
+         * late the animation runs long rather than jumping. Returns whether there's more.
+         */
+        pub fn step(&mut self, screen: &mut Screen, now: Instant) -> bool {
+            let elapsed = now.saturating_duration_since(self.started);
+            let due = (elapsed.as_millis() / FRAME.as_millis()) as usize;
+            let next = self.shown.map_or(0, |shown| shown + 1);
+            if next <= due && next < self.frames.len() {
+                self.show(screen, next);
+            }
+            self.shown.map_or(0, |shown| shown + 1) < self.frames.len()
+        }
+
+        /// Skips to the last frame.
+        pub fn finish(&mut self, screen: &mut Screen) {
+            let last = self.frames.len().saturating_sub(1);
+            if self.shown != Some(last) {
+                self.show(screen, last);
+            }
+        }
+
+        fn show(&mut self, screen: &mut Screen, frame: usize) {
+            for &(at, ch, style) in self.frames.get(frame).into_iter().flatten() {
+                screen.write_styled(&at, ch, style);
+            }
+            self.shown = Some(frame);
+        }
+    }
+
+    #[test]
+    fn test_animation() {
+        let mut screen = Screen::default();
+        let start = Instant::now();
+        let green = Style {
+            bg: Some(crossterm::style::Color::Green),
+            ..Style::default()
+        };
+        let tiles: Vec<Cell> = "AB"
+            .chars()
+            .enumerate()
+            .map(|(i, ch)| (Position::new(i as i32, 1), ch, green))
+            .collect();
+
+        let mut reveal = Animation::reveal(&tiles, Style::default(), start);
+        assert!(reveal.step(&mut screen, start));
+        assert!(reveal.step(&mut screen, start + FRAME));
+        screen.flush();
+        assert_eq!(screen.style(&(0, 1).into()), green);
+        assert_eq!(screen.style(&(1, 1).into()), Style::default());
+
+        // Nothing's written again until the next frame is due.
+        reveal.step(&mut screen, start + FRAME + FRAME / 2);
+        assert!(screen.flush().is_empty());
+
+        let above = [(Position::new(0, 0), 'x', Style::default())];
+        let mut all = reveal.then(Animation::bounce(&tiles, &above, start));
+        // Late steps still show every frame, one at a time.
+        assert!(all.step(&mut screen, start + FRAME * 5));
+        screen.flush();
+        assert_eq!(screen.style(&(1, 1).into()), green);
+        assert_eq!(screen.read(&(0, 0).into()), BLANK);
+        assert!(all.step(&mut screen, start + FRAME * 5));
+        screen.flush();
+        assert_eq!(screen.read(&(0, 0).into()), 'A');
+        assert_eq!(screen.read(&(0, 1).into()), BLANK);
+
+        all.finish(&mut screen);
+        screen.flush();
+        assert_eq!(screen.read(&(0, 0).into()), 'x');
+        assert_eq!(screen.read(&(0, 1).into()), 'A');
+        assert_eq!(screen.style(&(1, 1).into()), green);
+    }
+}
+
+pub mod theme {
+    use crossterm::style::{Attribute, Attributes, Color};
+
+    use crate::ui::screen::Style;
+    use crate::wordl::{CLOSE, MATCH, MISS};
+
+    pub const NAMES: [&str; 4] = ["classic", "high-contrast", "mono", "custom"];
+
+    /// An RGB color, like `#6aaa64` in the config file.
+    pub type Rgb = (u8, u8, u8);
+
+    /// How many colors the terminal can show.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum Depth {
+        None,
+        Ansi16,
+        Ansi256,
+        TrueColor,
+    }
+
+    impl Depth {
+        /// Guesses from `$COLORTERM` and `$TERM`, like most terminal programs.
+        pub fn detect() -> Self {
+            let var = |name| std::env::var(name).unwrap_or_default();
+            let (colorterm, term) = (var("COLORTERM"), var("TERM"));
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                Depth::TrueColor
+            } else if term.contains("256color") {
+                Depth::Ansi256
+            } else if term == "dumb" {
+                Depth::None
+            } else {
+                Depth::Ansi16
+            }
+        }
+    }
+
+    /**
+     * How tiles and keys are drawn in each state, and the emoji used for them in shared grids.
+     * Each is in [MISS], [CLOSE], [MATCH] order. No emoji means different things in two themes,
+     * so [classic_emoji] can read any grid back.
+     */
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct Theme {
+        pub tiles: [Style; 3],
+        pub emoji: [char; 3],
+    }
+
+    impl Default for Theme {
+        fn default() -> Self {
+            Theme::classic(Depth::Ansi16)
+        }
+    }
+
+    impl Theme {
+        /// Wordle's grey, yellow and green, with the red miss emoji wordle-rs always had.
+        pub fn classic(depth: Depth) -> Self {
+            Self::colored(
+                [
+                    ((0x78, 0x7c, 0x7e), Color::DarkGrey),
+                    ((0xc9, 0xb4, 0x58), Color::Yellow),
+                    ((0x6a, 0xaa, 0x64), Color::Green),
+                ],
+                [MISS, CLOSE, MATCH],
+                depth,
+            )
+        }
+
+        /// Orange and blue, which most color blind players can tell apart.
+        pub fn high_contrast(depth: Depth) -> Self {
+            Self::colored(
+                [
+                    ((0x78, 0x7c, 0x7e), Color::DarkGrey),
+                    ((0x85, 0xc0, 0xf9), Color::Blue),
+                    ((0xf5, 0x79, 0x3a), Color::DarkYellow),
+                ],
+                ['⬛', '🟦', '🟧'],
+                depth,
+            )
+        }
+
+        /// No color at all: misses are dim, close letters underlined and matches bold and inverted.
+        pub fn mono() -> Self {
+            let attrs = |attrs: &[Attribute]| Style {
+                attrs: Attributes::from(attrs),
+                ..Style::default()
+            };
+            Self {
+                tiles: [
+                    attrs(&[Attribute::Dim]),
+                    attrs(&[Attribute::Underlined]),
+                    attrs(&[Attribute::Reverse, Attribute::Bold]),
+                ],
+                emoji: ['⬜', '🔳', '🔲'],
+            }
+        }
+
+        /// Your own colors, with the classic emoji.
+        pub fn custom(colors: [Rgb; 3], depth: Depth) -> Self {
+            let [miss, close, matched] = colors;
+            Self::colored(
+                [
+                    (miss, nearest_ansi16(miss)),
+                    (close, nearest_ansi16(close)),
+                    (matched, nearest_ansi16(matched)),
+                ],
+                [MISS, CLOSE, MATCH],
+                depth,
+            )
+        }
+
+        /// One of [NAMES]. `custom` needs `colors`. Terminals without color get [Theme::mono].
+        pub fn named(name: &str, colors: Option<[Rgb; 3]>, depth: Depth) -> Result<Self, String> {
+            let theme = match name {
+                "classic" => Self::classic(depth),
+                "high-contrast" => Self::high_contrast(depth),
+                "mono" => Self::mono(),
+                "custom" => colors
+                    .map(|colors| Self::custom(colors, depth))
+                    .ok_or("The custom theme needs [colors] in the config file.")?,
+                _ => return Err(format!("Unknown theme {:?}", name)),
+            };
+            Ok(if depth == Depth::None {
+                Self::mono()
+            } else {
+                theme
+            })
+        }
+
+        /// `colors` are each an RGB color and what to use instead on a 16-color terminal.
+        fn colored(colors: [(Rgb, Color); 3], emoji: [char; 3], depth: Depth) -> Self {
+            let tile = |(rgb, ansi16): (Rgb, Color)| {
+                let (r, g, b) = rgb;
+                // Dark text on light tiles and the other way around.
+                let light = 299 * r as u32 + 587 * g as u32 + 114 * b as u32 > 128_000;
+                Style {
+                    fg: Some(if light { Color::Black } else { Color::White }),
+                    bg: Some(match depth {
+                        Depth::TrueColor => Color::Rgb { r, g, b },
+                        Depth::Ansi256 => Color::AnsiValue(ansi256(rgb)),
+                        _ => ansi16,
+                    }),
+                    ..Style::default()
+                }
+            };
+            Self {
+                tiles: colors.map(tile),
+                emoji,
+            }
+        }
+
+        /// The style of a tile showing `cmp`, one of [MISS], [CLOSE] or [MATCH].
+        pub fn tile(&self, cmp: char) -> Style {
+            self.tiles[index(cmp)]
+        }
+
+        /// Swaps the classic emoji in `text` for this theme's.
+        pub fn recolor(&self, text: &str) -> String {
+            text.chars()
+                .map(|ch| match ch {
+                    MISS | CLOSE | MATCH => self.emoji[index(ch)],
+                    _ => ch,
+                })
+                .collect()
+        }
+    }
+
+    fn index(cmp: char) -> usize {
+        match cmp {
+            MATCH => 2,
+            CLOSE => 1,
+            _ => 0,
+        }
+    }
+
+    /// The classic emoji for any theme's emoji, so shared grids can be read back.
+    pub fn classic_emoji(ch: char) -> Option<char> {
+        [Theme::high_contrast(Depth::Ansi16), Theme::mono()]
+            .iter()
+            .find_map(|theme| theme.emoji.iter().position(|&e| e == ch))
+            .map(|i| [MISS, CLOSE, MATCH][i])
+    }
+
+    /// Parses `#rrggbb`.
+    pub fn rgb(hex: &str) -> Result<Rgb, String> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let channel = |i: usize| {
+            digits
+                .get(i..i + 2)
+                .and_then(|d| u8::from_str_radix(d, 16).ok())
+        };
+        match (digits.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok((r, g, b)),
+            _ => Err(format!("Bad color {:?}. Use #rrggbb.", hex)),
+        }
+    }
+
+    /// The closest color in the 6x6x6 cube of 256-color terminals.
+    fn ansi256((r, g, b): Rgb) -> u8 {
+        let level = |c: u8| ((c as u16 * 5 + 127) / 255) as u8;
+        16 + 36 * level(r) + 6 * level(g) + level(b)
+    }
+
+    fn nearest_ansi16(rgb: Rgb) -> Color {
+        const BASIC: [(Rgb, Color); 8] = [
+            ((0x00, 0x00, 0x00), Color::Black),
+            ((0x80, 0x80, 0x80), Color::DarkGrey),
+            ((0xcd, 0x00, 0x00), Color::Red),
+            ((0x00, 0xcd, 0x00), Color::Green),
+            ((0xcd, 0xcd, 0x00), Color::Yellow),
+            ((0x00, 0x00, 0xee), Color::Blue),
+            ((0xcd, 0x00, 0xcd), Color::Magenta),
+            ((0x00, 0xcd, 0xcd), Color::Cyan),
+        ];
+        let distance = |(r, g, b): Rgb| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, rgb.0) + d(g, rgb.1) + d(b, rgb.2)
+        };
+        BASIC
+            .iter()
+            .min_by_key(|(basic, _)| distance(*basic))
+            .map(|&(_, color)| color)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_theme() {
+        assert_eq!(rgb("#6aaa64"), Ok((0x6a, 0xaa, 0x64)));
+        assert!(rgb("#6aaa6").is_err());
+        assert!(rgb("#6aaa6g").is_err());
+
+        let classic = Theme::named("classic", None, Depth::TrueColor).unwrap();
+        assert_eq!(
+            classic.tile(MATCH).bg,
+            Some(Color::Rgb {
+                r: 0x6a,
+                g: 0xaa,
+                b: 0x64
+            })
+        );
+        assert_eq!(Theme::default().tile(CLOSE).bg, Some(Color::Yellow));
+        assert_eq!(
+            Theme::high_contrast(Depth::Ansi256).tile(MATCH).bg,
+            Some(Color::AnsiValue(209))
+        );
+        assert_eq!(
+            Theme::named("classic", None, Depth::None),
+            Ok(Theme::mono())
+        );
+        assert!(Theme::named("custom", None, Depth::TrueColor).is_err());
+        assert!(Theme::named("custom", None, Depth::None).is_err());
+        assert!(Theme::named("nonsense", None, Depth::None).is_err());
+        let custom = Theme::custom([(0, 0, 0), (0xff, 0x80, 0), (0, 0, 0xff)], Depth::Ansi16);
+        assert_eq!(custom.tile(MATCH).bg, Some(Color::Blue));
+
+        let shared = Theme::high_contrast(Depth::Ansi16).recolor("🟥🟨🟩 3/6");
+        assert_eq!(shared, "⬛🟦🟧 3/6");
+        assert_eq!(
+            shared.chars().filter_map(classic_emoji).collect::<Vec<_>>(),
+            vec![MISS, CLOSE, MATCH]
+        );
+
+        let row = crate::wordl::join(&[MATCH, MISS, CLOSE, MISS, MATCH], false);
+        for name in NAMES {
+            let theme = Theme::named(name, Some([(0, 0, 0); 3]), Depth::Ansi16).unwrap();
+            assert_eq!(
+                crate::wordl::split(&theme.recolor(&row)),
+                Some(vec![MATCH, MISS, CLOSE, MISS, MATCH]),
+                "{}",
+                name
+            );
+        }
+    }
 }